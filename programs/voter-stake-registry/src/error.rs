@@ -116,4 +116,106 @@ pub enum VsrError {
     // 6037 / 0x1795
     #[msg("")]
     InvalidTimestampArguments,
+    // 6038 / 0x1796
+    #[msg("")]
+    TooManySupplyExclusions,
+    // 6039 / 0x1797
+    #[msg("")]
+    SupplyExclusionAccountNotFound,
+    // 6040 / 0x1798
+    #[msg("")]
+    InvalidLockupWarmupFactor,
+    // 6041 / 0x1799
+    #[msg("")]
+    UnlockEarlyDisabled,
+    // 6042 / 0x179a
+    #[msg("")]
+    InvalidUnlockEarlyPenalty,
+    // 6043 / 0x179b
+    #[msg("")]
+    UnlockEarlyNotAllowedOnClawbackDeposit,
+    // 6044 / 0x179c
+    #[msg("")]
+    InvalidPenaltyDestination,
+    // 6045 / 0x179d
+    #[msg("")]
+    InvalidClawbackDestination,
+    // 6046 / 0x179e
+    #[msg("")]
+    ClawbackAuthorityOnlyForGrants,
+    // 6047 / 0x179f
+    #[msg("")]
+    InvalidGrantBatchAccounts,
+    // 6048 / 0x17a0
+    #[msg("")]
+    GrantAlreadyStarted,
+    // 6049 / 0x17a1
+    #[msg("")]
+    GrantNotFundedOverTime,
+    // 6050 / 0x17a2
+    #[msg("")]
+    GrantFundingExceedsVested,
+    // 6051 / 0x17a3
+    #[msg("")]
+    InvalidChangeToGrantFundedOverTime,
+    // 6052 / 0x17a4
+    #[msg("")]
+    VoterHasOutstandingVotes,
+    // 6053 / 0x17a5
+    #[msg("")]
+    DepositEntryHasLockDelegate,
+    // 6054 / 0x17a6
+    #[msg("")]
+    InvalidVoterWeightSnapshot,
+    // 6055 / 0x17a7
+    #[msg("")]
+    VoterWeightCheckpointsNotFound,
+    // 6056 / 0x17a8
+    #[msg("")]
+    NoVoterWeightCheckpointBeforeTimestamp,
+    // 6057 / 0x17a9
+    #[msg("")]
+    InvalidDelegate,
+    // 6058 / 0x17aa
+    #[msg("")]
+    InvalidDelegator,
+    // 6059 / 0x17ab
+    #[msg("")]
+    TooManyDelegators,
+    // 6060 / 0x17ac
+    #[msg("")]
+    TooManyVoteLocks,
+    // 6061 / 0x17ad
+    #[msg("")]
+    VoteLockNotReleasable,
+    // 6062 / 0x17ae
+    #[msg("")]
+    DelegationsRequireVote,
+    // 6063 / 0x17af
+    #[msg("")]
+    VoterWeightRecordNotFound,
+    // 6064 / 0x17b0
+    #[msg("")]
+    InvalidVoterWeightRecordPairs,
+    // 6065 / 0x17b1
+    #[msg("")]
+    DuplicateSupplyExclusion,
+    // 6066 / 0x17b2
+    #[msg("")]
+    InvalidChangeToRevocableGrant,
+    // 6067 / 0x17b3
+    #[msg("")]
+    LockDelegateNotAllowedOnClawbackDeposit,
+    // 6068 / 0x17b4
+    #[msg("")]
+    InvalidVoteProposal,
+    // 6069 / 0x17b5
+    #[msg("")]
+    DepositTotalsNotTracked,
+    // 6070 / 0x17b6
+    #[msg("")]
+    InvalidLegacyAccount,
+    // 6071 / 0x17b7
+    #[msg("")]
+    InvalidVoterWeightAction,
 }
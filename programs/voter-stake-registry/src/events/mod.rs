@@ -9,6 +9,26 @@ pub struct VoterInfo {
     pub voting_power_baseline: u64,
 }
 
+#[event]
+#[derive(Debug)]
+pub struct MaxVoteWeightInfo {
+    /// Max vote weight of the registrar, as computed by update_max_vote_weight
+    pub max_vote_weight: u64,
+}
+
+#[event]
+#[derive(Debug)]
+pub struct VoterWeightChanged {
+    /// The Voter account
+    pub voter: Pubkey,
+    /// The voter's authority, the governing token owner of its VoterWeightRecord
+    pub voter_authority: Pubkey,
+    /// Weight in the VoterWeightRecord before update_voter_weight_records
+    pub previous_voter_weight: u64,
+    /// Weight written by update_voter_weight_records
+    pub voter_weight: u64,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Debug)]
 pub struct VestingInfo {
     /// Amount of tokens vested each period
@@ -40,4 +60,11 @@ pub struct DepositEntryInfo {
     pub voting_power_baseline: u64,
     /// Information about locking, if any
     pub locking: Option<LockingInfo>,
+    /// The authority that granted the deposit, or Pubkey::default() if the
+    /// deposit was not created by grant
+    pub grantor: Pubkey,
+    /// The lock delegate keeping the deposit in place, Pubkey::default() if none
+    pub lock_delegate: Pubkey,
+    /// The voter the deposit's vote weight is delegated to, Pubkey::default() if none
+    pub delegate: Pubkey,
 }
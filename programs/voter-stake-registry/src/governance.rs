@@ -3,6 +3,9 @@
 macro_rules! vote_weight_record {
     ($id:expr) => {
         /// Anchor wrapper for the SPL governance program's VoterWeightRecord type.
+        ///
+        /// Reads records in the format of spl-governance v2 and v3, which differ
+        /// only in the discriminator, see VoterWeightRecordVersion.
         #[derive(Clone)]
         pub struct VoterWeightRecord(spl_governance_addin_api::voter_weight::VoterWeightRecord);
 
@@ -12,7 +15,12 @@ macro_rules! vote_weight_record {
                 let vwr: spl_governance_addin_api::voter_weight::VoterWeightRecord =
                     anchor_lang::AnchorDeserialize::deserialize(&mut data)
                         .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
-                if !solana_program::program_pack::IsInitialized::is_initialized(&vwr) {
+                // accept the formats of both spl-governance v2 and v3
+                if $crate::state::VoterWeightRecordVersion::from_discriminator(
+                    &vwr.account_discriminator,
+                )
+                .is_none()
+                {
                     return Err(anchor_lang::error::ErrorCode::AccountDidNotSerialize.into());
                 }
                 Ok(VoterWeightRecord(vwr))
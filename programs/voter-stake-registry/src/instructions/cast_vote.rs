@@ -0,0 +1,135 @@
+use crate::instructions::write_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use spl_governance::state::vote_record::Vote;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(has_one = realm, has_one = governance_program_id)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority,
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_weight_record_bump,
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.voter_authority,
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.realm_governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// The voter's VoterWeightSnapshot for the proposal, see
+    /// create_voter_weight_snapshot
+    ///
+    /// Verification inline in instruction
+    pub voter_weight_snapshot: UncheckedAccount<'info>,
+
+    /// The spl-governance program of the registrar
+    pub governance_program_id: UncheckedAccount<'info>,
+
+    /// The accounts below are verified by spl-governance
+    pub realm: UncheckedAccount<'info>,
+    pub realm_config: UncheckedAccount<'info>,
+    pub governance: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub proposal: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub proposal_owner_record: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub voter_token_owner_record: UncheckedAccount<'info>,
+    pub governing_token_mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vote_record: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Refreshes the voter weight record for voting on `proposal` and casts the
+/// vote through spl-governance in one instruction.
+///
+/// `vote`: The spl-governance vote to cast.
+///
+/// The record is written like update_voter_weight_record does for CastVote
+/// with the proposal as target, so the voter's VoterWeightSnapshot for the
+/// proposal must exist and the weight is locked for the proposal.
+///
+/// Delegates that vote with delegated weight must use
+/// update_voter_weight_record_with_delegations and spl-governance directly.
+pub fn cast_vote(ctx: Context<CastVote>, vote: Vote) -> Result<()> {
+    {
+        let registrar = &ctx.accounts.registrar.load()?;
+        let voter = &mut ctx.accounts.voter.load_mut()?;
+        write_voter_weight_record(
+            registrar,
+            &ctx.accounts.voter.key(),
+            voter,
+            &mut ctx.accounts.voter_weight_record,
+            Some(VoterWeightAction::CastVote),
+            Some(ctx.accounts.proposal.key()),
+            &[
+                ctx.accounts.voter_weight_snapshot.to_account_info(),
+                ctx.accounts.governance.to_account_info(),
+                ctx.accounts.proposal.to_account_info(),
+                ctx.accounts.voter_authority.to_account_info(),
+            ],
+            ctx.program_id,
+        )?;
+    }
+    // spl-governance must see the updated record
+    ctx.accounts.voter_weight_record.exit(ctx.program_id)?;
+
+    let a = &ctx.accounts;
+    let instruction = spl_governance::instruction::cast_vote(
+        a.governance_program_id.key,
+        a.realm.key,
+        a.governance.key,
+        a.proposal.key,
+        a.proposal_owner_record.key,
+        a.voter_token_owner_record.key,
+        a.voter_authority.key,
+        a.governing_token_mint.key,
+        a.payer.key,
+        Some(a.voter_weight_record.key()),
+        None,
+        vote,
+    );
+    invoke(
+        &instruction,
+        &[
+            a.realm.to_account_info(),
+            a.governance.to_account_info(),
+            a.proposal.to_account_info(),
+            a.proposal_owner_record.to_account_info(),
+            a.voter_token_owner_record.to_account_info(),
+            a.voter_authority.to_account_info(),
+            a.vote_record.to_account_info(),
+            a.governing_token_mint.to_account_info(),
+            a.payer.to_account_info(),
+            a.system_program.to_account_info(),
+            a.rent.to_account_info(),
+            a.realm_config.to_account_info(),
+            a.voter_weight_record.to_account_info(),
+            a.governance_program_id.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
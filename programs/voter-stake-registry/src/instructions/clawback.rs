@@ -1,4 +1,5 @@
 use crate::error::*;
+use crate::instructions::reset_voter_weight_record_in_accounts;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::token;
@@ -6,9 +7,13 @@ use anchor_spl::token::{Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct Clawback<'info> {
-    #[account(has_one = realm_authority)]
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
-    pub realm_authority: Signer<'info>,
+
+    /// The realm authority, or the clawback authority of the deposit's voting mint
+    ///
+    /// Verification inline in instruction
+    pub authority: Signer<'info>,
 
     // checking the PDA address it just an extra precaution,
     // the other constraints must be exhaustive
@@ -47,19 +52,63 @@ impl<'info> Clawback<'info> {
 /// Claws back locked tokens from a deposit entry.
 ///
 /// `deposit_entry_index`: The index of the deposit entry to claw back tokens on.
+/// `amount`: The amount of locked tokens to claw back, None for all of them.
 ///
 /// The deposit entry must have been created with `allow_clawback=true`.
 ///
-/// The instruction will always reclaim all locked tokens, while leaving tokens
-/// that have already vested in place.
-pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
-    let locked_amount = {
+/// Tokens that have already vested are left in place. When all locked tokens
+/// are reclaimed, the lockup ends. Otherwise the remaining locked tokens keep
+/// vesting on the same schedule.
+///
+/// For grants funded over time, which hold no locked tokens, clawback cancels
+/// the promise of further funding.
+///
+/// If the voting mint has a clawback destination configured with
+/// configure_clawback_destination, `destination` must be that account.
+///
+/// `authority` must be the realm authority or, for deposit entries created by
+/// grant, the clawback authority of the deposit's voting mint.
+///
+/// If the registrar uses `ClawbackPolicy::RequireNoOutstandingVotes`, the
+/// voter's token owner record must be passed as the first remaining account.
+///
+/// Unless the registrar uses `VoterWeightExpiry::CurrentSlot`, the voter's
+/// VoterWeightRecord must be passed in ctx.remaining_accounts, writable, to be
+/// updated with the reduced weight.
+pub fn clawback(
+    ctx: Context<Clawback>,
+    deposit_entry_index: u8,
+    amount: Option<u64>,
+) -> Result<()> {
+    let (clawback_amount, deposit_totals) = {
         // Load the accounts.
         let registrar = &ctx.accounts.registrar.load()?;
         let voter = &mut ctx.accounts.voter.load_mut()?;
+        let mut deposit_totals = DepositTotalsChange::new(registrar, voter);
 
-        // Note: don't assert if token_owner_record is engaged in active proposals
-        // since this way a grantee could block clawback
+        // Note: by default, don't assert if token_owner_record is engaged in active
+        // proposals since this way a grantee could block clawback. With the
+        // RequireNoOutstandingVotes policy the grantee can only delay it until the
+        // votes can be relinquished without their consent.
+        let mint_idx = voter
+            .active_deposit_mut(deposit_entry_index)?
+            .voting_mint_config_idx as usize;
+        if registrar.clawback_policy == ClawbackPolicy::RequireNoOutstandingVotes
+            && registrar.voting_mints[mint_idx].grants_vote_weight()
+        {
+            let token_owner_record_info = ctx
+                .remaining_accounts
+                .first()
+                .ok_or_else(|| error!(VsrError::InvalidTokenOwnerRecord))?;
+            let token_owner_record =
+                voter.load_token_owner_record(token_owner_record_info, registrar)?;
+            require_eq!(
+                token_owner_record.unrelinquished_votes_count,
+                0,
+                VsrError::VoterHasOutstandingVotes
+            );
+            voter.assert_no_active_votes()?;
+        }
 
         // Get the deposit being clawed back from.
         let deposit_entry = voter.active_deposit_mut(deposit_entry_index)?;
@@ -67,25 +116,85 @@ pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
             deposit_entry.allow_clawback,
             VsrError::ClawbackNotAllowedOnDeposit
         );
+        require!(
+            !deposit_entry.has_lock_delegate(),
+            VsrError::DepositEntryHasLockDelegate
+        );
+
+        let mint_config = &registrar.voting_mints[deposit_entry.voting_mint_config_idx as usize];
+        let authority = ctx.accounts.authority.key();
+        if authority != registrar.realm_authority {
+            require!(
+                mint_config.clawback_authority != Pubkey::default()
+                    && authority == mint_config.clawback_authority,
+                VsrError::InvalidAuthority
+            );
+            require!(
+                deposit_entry.is_grant(),
+                VsrError::ClawbackAuthorityOnlyForGrants
+            );
+        }
+        if mint_config.clawback_destination != Pubkey::default() {
+            require_keys_eq!(
+                ctx.accounts.destination.key(),
+                mint_config.clawback_destination,
+                VsrError::InvalidClawbackDestination
+            );
+        }
 
+        let deposit_entry_before = *deposit_entry;
         let curr_ts = registrar.clock_unix_timestamp();
         let locked_amount = deposit_entry.amount_locked(curr_ts);
+        let clawback_amount = amount.unwrap_or(locked_amount);
+        require_gte!(
+            locked_amount,
+            clawback_amount,
+            VsrError::InsufficientLockedTokens
+        );
 
         // Update deposit book keeping.
         require_gte!(
             deposit_entry.amount_deposited_native,
-            locked_amount,
+            clawback_amount,
             VsrError::InternalProgramError
         );
-        deposit_entry.amount_deposited_native -= locked_amount;
+        deposit_entry.amount_deposited_native -= clawback_amount;
 
-        // Now that all locked funds are withdrawn, end the lockup
-        let curr_ts = registrar.clock_unix_timestamp();
-        deposit_entry.amount_initially_locked_native = 0;
-        deposit_entry.lockup = Lockup::new_from_periods(LockupKind::None, curr_ts, curr_ts, 0)?;
-        deposit_entry.allow_clawback = false;
-        locked_amount
+        if clawback_amount == locked_amount {
+            // Now that all locked funds are withdrawn, end the lockup
+            deposit_entry.amount_initially_locked_native = 0;
+            deposit_entry.lockup = Lockup::new_from_periods(LockupKind::None, curr_ts, curr_ts, 0)?;
+            deposit_entry.allow_clawback = false;
+            // Grants funded over time don't promise any further tokens.
+            deposit_entry.grant_promised_native = deposit_entry.grant_funded_native;
+        } else {
+            // Turn vested tokens into unlocked ones, so that the remaining periods
+            // of the schedule vest exactly the tokens that stay locked.
+            deposit_entry.resolve_vesting(curr_ts)?;
+            deposit_entry.amount_initially_locked_native = deposit_entry
+                .amount_initially_locked_native
+                .checked_sub(clawback_amount)
+                .unwrap();
+        }
+        deposit_totals.record(&deposit_entry_before, deposit_entry);
+
+        record_voter_weight_checkpoint(
+            &ctx.accounts.voter.key(),
+            voter,
+            registrar,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        reset_voter_weight_record_in_accounts(
+            registrar,
+            voter,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        (clawback_amount, deposit_totals)
     };
+    deposit_totals.apply(&ctx.accounts.registrar)?;
 
     {
         // Transfer the tokens to withdraw.
@@ -93,7 +202,7 @@ pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
         let voter_seeds = voter_seeds!(voter);
         token::transfer(
             ctx.accounts.transfer_ctx().with_signer(&[voter_seeds]),
-            locked_amount,
+            clawback_amount,
         )?;
     }
 
@@ -25,6 +25,10 @@ pub fn close_deposit_entry(ctx: Context<CloseDepositEntry>, deposit_entry_index:
     let voter = &mut ctx.accounts.voter.load_mut()?;
     let d = voter.active_deposit_mut(deposit_entry_index)?;
     require_eq!(d.amount_deposited_native, 0, VsrError::VotingTokenNonZero);
+    require!(
+        !d.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
 
     // Deposits that have clawback enabled are guaranteed to live until the end
     // of their locking period. That ensures a deposit can't be closed and reopenend
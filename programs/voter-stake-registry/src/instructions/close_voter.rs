@@ -3,12 +3,14 @@ use std::ops::DerefMut;
 use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
 use anchor_spl::token::{self, CloseAccount, Token, TokenAccount};
 use bytemuck::bytes_of_mut;
 
 // Remaining accounts must be all the token token accounts owned by voter, he wants to close,
 // they should be writable so that they can be closed and sol required for rent
-// can then be sent back to the sol_destination
+// can then be sent back to the sol_destination. The voter's VoterWeightCheckpoints,
+// if it has them, must be passed as well.
 #[derive(Accounts)]
 pub struct CloseVoter<'info> {
     pub registrar: AccountLoader<'info, Registrar>,
@@ -35,6 +37,9 @@ pub struct CloseVoter<'info> {
 /// Closes the voter account (Optionally, also token vaults, as part of remaining_accounts),
 /// allowing one to retrieve rent exemption SOL.
 /// Only accounts with no remaining deposits can be closed.
+///
+/// The voter's VoterWeightCheckpoints are closed with it, so that a voter created
+/// again for the same voter_authority can create new ones.
 pub fn close_voter<'key, 'accounts, 'remaining, 'info>(
     ctx: Context<'key, 'accounts, 'remaining, 'info, CloseVoter<'info>>,
 ) -> Result<()> {
@@ -44,9 +49,26 @@ pub fn close_voter<'key, 'accounts, 'remaining, 'info>(
             sum.checked_add(d.amount_deposited_native).unwrap()
         });
         require_eq!(amount, 0, VsrError::VotingTokenNonZero);
+        require!(
+            !voter.deposits.iter().any(|d| d.has_lock_delegate()),
+            VsrError::DepositEntryHasLockDelegate
+        );
+
+        let mut token_accounts = ctx.remaining_accounts.iter().collect::<Vec<_>>();
+        if voter.has_voter_weight_checkpoints {
+            let checkpoints_info = find_voter_weight_checkpoints(
+                &ctx.accounts.voter.key(),
+                &voter,
+                ctx.remaining_accounts,
+                ctx.program_id,
+            )?;
+            AccountLoader::<VoterWeightCheckpoints>::try_from(checkpoints_info)?
+                .close(ctx.accounts.sol_destination.to_account_info())?;
+            token_accounts.retain(|a| a.key() != checkpoints_info.key());
+        }
 
         let voter_seeds = voter_seeds!(voter);
-        for account in &mut ctx.remaining_accounts.iter() {
+        for account in token_accounts {
             let token = Account::<TokenAccount>::try_from(&account.clone()).unwrap();
             require_keys_eq!(
                 token.owner,
@@ -0,0 +1,34 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ConfigureClawbackAuthority<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    /// The already configured voting mint whose clawback authority should change
+    pub mint: Account<'info, Mint>,
+}
+
+/// Sets an authority that may claw back granted tokens of a voting mint.
+///
+/// * `idx`: index of the voting mint config of `mint`, see Registrar::voting_mint_config_mut
+/// * `clawback_authority`: the new clawback authority, None to remove it
+///
+/// The clawback authority can only claw back deposit entries that were created
+/// by grant and allow clawback. The realm authority can still claw back any
+/// deposit entry that allows clawback.
+pub fn configure_clawback_authority(
+    ctx: Context<ConfigureClawbackAuthority>,
+    idx: u16,
+    clawback_authority: Option<Pubkey>,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let config = registrar.voting_mint_config_mut(idx, ctx.accounts.mint.key())?;
+
+    config.clawback_authority = clawback_authority.unwrap_or_default();
+
+    Ok(())
+}
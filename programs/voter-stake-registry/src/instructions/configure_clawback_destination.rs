@@ -0,0 +1,34 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ConfigureClawbackDestination<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    /// The already configured voting mint whose clawback destination should change
+    pub mint: Account<'info, Mint>,
+}
+
+/// Restricts where clawed back tokens of a voting mint may be sent.
+///
+/// * `idx`: index of the voting mint config of `mint`, see Registrar::voting_mint_config_mut
+/// * `clawback_destination`: token account of `mint` that clawback must send tokens to,
+///   None allows any token account
+///
+/// Setting this makes it impossible for a single clawback instruction, for example
+/// in a malicious proposal, to redirect clawed back tokens elsewhere.
+pub fn configure_clawback_destination(
+    ctx: Context<ConfigureClawbackDestination>,
+    idx: u16,
+    clawback_destination: Option<Pubkey>,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let config = registrar.voting_mint_config_mut(idx, ctx.accounts.mint.key())?;
+
+    config.clawback_destination = clawback_destination.unwrap_or_default();
+
+    Ok(())
+}
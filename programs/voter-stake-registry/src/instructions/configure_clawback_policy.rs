@@ -0,0 +1,29 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureClawbackPolicy<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Selects whether clawback takes the votes of the voter into account.
+///
+/// With `ClawbackPolicy::IgnoreVotes` clawback works regardless of the voter's
+/// votes, so clawed back tokens may stay counted on proposals the voter already
+/// voted on.
+///
+/// With `ClawbackPolicy::RequireNoOutstandingVotes` clawback of tokens that
+/// contribute vote weight needs the voter's token owner record and fails while
+/// it has unrelinquished votes or vote locks. A grantee can only delay the
+/// clawback until voting on these proposals has ended: after that anyone can
+/// relinquish the votes through spl-governance and call release_vote_locks.
+pub fn configure_clawback_policy(
+    ctx: Context<ConfigureClawbackPolicy>,
+    policy: ClawbackPolicy,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.clawback_policy = policy;
+    Ok(())
+}
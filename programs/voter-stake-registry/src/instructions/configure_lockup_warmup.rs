@@ -0,0 +1,47 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ConfigureLockupWarmup<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    /// The already configured voting mint whose lockup warmup should change
+    pub mint: Account<'info, Mint>,
+}
+
+/// Configures the extra lockup vote weight of lockups that have not started yet.
+///
+/// * `idx`: index of the voting mint config of `mint`, see Registrar::voting_mint_config_mut
+/// * `pre_start_lockup_bonus_scaled_factor`: fraction of the extra lockup vote weight
+///   that deposits get before their lockup's start_ts, in 1/SCALED_FACTOR_BASE units.
+///   Zero means such deposits only have baseline vote weight. None disables the
+///   warmup, so future-dated lockups are treated as starting now.
+///
+/// Without a warmup, a lockup starting in a year gets the same bonus as one
+/// starting today, since the remaining lockup time is measured from now.
+///
+/// The warmup only ever reduces vote weight, so it never affects the max vote weight.
+pub fn configure_lockup_warmup(
+    ctx: Context<ConfigureLockupWarmup>,
+    idx: u16,
+    pre_start_lockup_bonus_scaled_factor: Option<u64>,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let config = registrar.voting_mint_config_mut(idx, ctx.accounts.mint.key())?;
+    match pre_start_lockup_bonus_scaled_factor {
+        Some(factor) => {
+            VotingMintConfig::validate_lockup_warmup_factor(factor)?;
+            config.lockup_warmup_enabled = true;
+            config.pre_start_lockup_bonus_scaled_factor = factor;
+        }
+        None => {
+            config.lockup_warmup_enabled = false;
+            config.pre_start_lockup_bonus_scaled_factor = 0;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureMaxVoteWeightSource<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Selects how the max vote weight of the registrar is computed.
+///
+/// With `MaxVoteWeightSource::Supply` the supply of all voting mints is used,
+/// assuming every token was deposited with the maximum lockup bonus.
+///
+/// With `MaxVoteWeightSource::Deposits` only the tokens actually deposited are
+/// used: the baseline vote weight of all deposits plus the max extra lockup vote
+/// weight of all locked deposits. That makes quorums meaningful for realms where
+/// most of the supply never enters the registrar.
+///
+/// Switching to `Deposits` resets the deposit totals and starts a new
+/// deposit_totals_epoch. Voters that already exist are only counted after
+/// sync_deposit_totals was called for them, voters created later are counted
+/// right away. Only tracking deposit totals with this source means that the
+/// registrar only needs to be writable in instructions that change deposits
+/// while it is selected.
+pub fn configure_max_vote_weight_source(
+    ctx: Context<ConfigureMaxVoteWeightSource>,
+    source: MaxVoteWeightSource,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    if source == MaxVoteWeightSource::Deposits
+        && registrar.max_vote_weight_source != MaxVoteWeightSource::Deposits
+    {
+        for config in registrar.voting_mints.iter_mut() {
+            config.deposited_native_total = 0;
+            config.lockup_weighted_native_total = 0;
+        }
+        registrar.deposit_totals_epoch = registrar.deposit_totals_epoch.checked_add(1).unwrap();
+    }
+    registrar.max_vote_weight_source = source;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use crate::error::*;
+use crate::instructions::is_freshly_initialized;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use std::mem::size_of;
+
+// Remaining accounts must be all the excluded token accounts.
+#[derive(Accounts)]
+pub struct ConfigureSupplyExclusions<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"supply-exclusions".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<SupplyExclusions>(),
+    )]
+    pub supply_exclusions: AccountLoader<'info, SupplyExclusions>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Sets the token accounts whose balances do not count as circulating supply.
+///
+/// `token_accounts`: the excluded token accounts, at most MAX_SUPPLY_EXCLUSIONS
+/// and without duplicates. Each must hold tokens of a configured voting mint
+/// and be passed in ctx.remaining_accounts.
+///
+/// When computing the max vote weight, the balances of these accounts are
+/// subtracted from their mint's supply. Passing an empty list disables
+/// the exclusions again.
+pub fn configure_supply_exclusions(
+    ctx: Context<ConfigureSupplyExclusions>,
+    token_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    require_gte!(
+        MAX_SUPPLY_EXCLUSIONS,
+        token_accounts.len(),
+        VsrError::TooManySupplyExclusions
+    );
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+
+    for (i, key) in token_accounts.iter().enumerate() {
+        require!(
+            !token_accounts[..i].contains(key),
+            VsrError::DuplicateSupplyExclusion
+        );
+        let token_account_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == *key)
+            .ok_or_else(|| error!(VsrError::SupplyExclusionAccountNotFound))?;
+        let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+        registrar.voting_mint_config_index(token_account.mint)?;
+    }
+
+    let new_exclusions = is_freshly_initialized(ctx.accounts.supply_exclusions.as_ref())?;
+    let mut exclusions = if new_exclusions {
+        ctx.accounts.supply_exclusions.load_init()?
+    } else {
+        ctx.accounts.supply_exclusions.load_mut()?
+    };
+    if new_exclusions {
+        exclusions.registrar = ctx.accounts.registrar.key();
+        exclusions.bump = *ctx.bumps.get("supply_exclusions").unwrap();
+    }
+    exclusions.token_accounts = [Pubkey::default(); MAX_SUPPLY_EXCLUSIONS];
+    exclusions.token_accounts[..token_accounts.len()].copy_from_slice(&token_accounts);
+
+    registrar.supply_exclusions = if token_accounts.is_empty() {
+        Pubkey::default()
+    } else {
+        ctx.accounts.supply_exclusions.key()
+    };
+
+    Ok(())
+}
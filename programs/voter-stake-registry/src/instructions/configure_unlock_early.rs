@@ -0,0 +1,36 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct ConfigureUnlockEarly<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    /// The already configured voting mint whose unlock early settings should change
+    pub mint: Account<'info, Mint>,
+}
+
+/// Allows or forbids unlocking locked tokens of a voting mint early, against a penalty.
+///
+/// * `idx`: index of the voting mint config of `mint`, see Registrar::voting_mint_config_mut
+/// * `penalty_destination`: token account of `mint` that receives the penalties,
+///   None forbids unlocking early
+/// * `max_penalty_scaled_factor`: penalty for unlocking tokens that would stay locked
+///   for lockup_saturation_secs or longer, in 1/SCALED_FACTOR_BASE units. Tokens with a
+///   shorter remaining lockup pay a proportional fraction of it.
+pub fn configure_unlock_early(
+    ctx: Context<ConfigureUnlockEarly>,
+    idx: u16,
+    penalty_destination: Option<Pubkey>,
+    max_penalty_scaled_factor: u64,
+) -> Result<()> {
+    VotingMintConfig::validate_unlock_early_penalty_factor(max_penalty_scaled_factor)?;
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let config = registrar.voting_mint_config_mut(idx, ctx.accounts.mint.key())?;
+    config.unlock_early_penalty_destination = penalty_destination.unwrap_or_default();
+    config.unlock_early_max_penalty_scaled_factor = max_penalty_scaled_factor;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Remaining accounts must be all the token mints that have registered
+// as voting mints.
+#[derive(Accounts)]
+pub struct ConfigureVoteWeightOutput<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Sets how internal vote weights are converted to the u64 vote weights
+/// used by spl-governance.
+///
+/// * `vote_weight_output_digit_shift`: how many digits to shift vote weights by
+///   when writing them to VoterWeightRecords and computing the max vote weight
+///
+/// Vote weights are computed as u128, so voting mints can use a positive digit_shift
+/// to keep precision without overflowing. A negative output digit shift then brings
+/// the resulting vote weights back into u64 range.
+///
+/// Example: With a 9 decimal token whose supply is close to the u64 limit, a voting
+/// mint with baseline_vote_weight_scaled_factor=1e9 and max_extra_lockup_vote_weight_scaled_factor=2e9
+/// would overflow. With vote_weight_output_digit_shift=-1 it does not.
+///
+/// Changing this while proposals are being voted on makes earlier and later votes
+/// incomparable.
+pub fn configure_vote_weight_output(
+    ctx: Context<ConfigureVoteWeightOutput>,
+    vote_weight_output_digit_shift: i8,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.vote_weight_output_digit_shift = vote_weight_output_digit_shift;
+
+    // Check for overflow in vote weight
+    registrar.max_vote_weight(ctx.remaining_accounts)?;
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+
+#[derive(Accounts)]
+pub struct ConfigureVoterWeightAction<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Sets the weight rule for a VoterWeightAction.
+///
+/// `action`: The governance action the rule applies to.
+/// `min_lockup_secs`: Only deposits that stay locked for at least this many
+///   seconds count towards the vote weight that update_voter_weight_record
+///   writes for `action`. Zero means all deposits count.
+///
+/// For example, requiring a minimum lockup for `CreateProposal` means only
+/// long-term lockers can create proposals, while all deposits count for voting.
+pub fn configure_voter_weight_action(
+    ctx: Context<ConfigureVoterWeightAction>,
+    action: VoterWeightAction,
+    min_lockup_secs: u64,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.voter_weight_action_min_lockup_secs[action as usize] = min_lockup_secs;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureVoterWeightExpiry<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Selects how long written VoterWeightRecords stay valid.
+///
+/// `expiry`: `VoterWeightExpiry::CurrentSlot` requires updating the record in
+///   the same transaction it's used in. `VoterWeightExpiry::Slots` keeps it
+///   valid for `slots` more slots and `VoterWeightExpiry::Never` keeps it valid
+///   until it's written again.
+/// `slots`: The number of slots for `VoterWeightExpiry::Slots`, ignored otherwise.
+///
+/// Instructions that can reduce a voter's weight, like withdraw or clawback,
+/// rewrite the voter's record. Unless records expire in the current slot, the
+/// ones that don't take the record as a named account then need it in their
+/// remaining accounts. Weight also decays over time, which a long lived record
+/// doesn't follow until it's updated again.
+pub fn configure_voter_weight_expiry(
+    ctx: Context<ConfigureVoterWeightExpiry>,
+    expiry: VoterWeightExpiry,
+    slots: u64,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.voter_weight_expiry = expiry;
+    registrar.voter_weight_expiry_slots = slots;
+    Ok(())
+}
@@ -0,0 +1,24 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ConfigureVoterWeightRecordVersion<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+}
+
+/// Selects the spl-governance VoterWeightRecord format that is written.
+///
+/// Must match the governance program of the realm: `VoterWeightRecordVersion::V2`
+/// for spl-governance v2 and `VoterWeightRecordVersion::V3` for v3. Records in
+/// either format are accepted as input, existing records switch to the new
+/// format the next time they are written.
+pub fn configure_voter_weight_record_version(
+    ctx: Context<ConfigureVoterWeightRecordVersion>,
+    version: VoterWeightRecordVersion,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.voter_weight_record_version = version;
+    Ok(())
+}
@@ -29,7 +29,8 @@ pub struct ConfigureVotingMint<'info> {
 ///   bonus is given to locked up deposits
 ///
 /// This instruction can be called several times for the same mint and index to
-/// change the voting mint configuration.
+/// change the voting mint configuration. The new factors apply immediately and
+/// any factor schedule set by configure_voting_mint_schedule is removed.
 ///
 /// The vote weight for `amount` of native tokens will be
 /// ```
@@ -43,8 +44,12 @@ pub struct ConfigureVotingMint<'info> {
 /// lockup_saturation_secs.
 ///
 /// Warning: Choose values that ensure that the vote weight will not overflow the
-/// u64 limit! There is a check based on the supply of all configured mints, but
-/// do your own checking too.
+/// u64 limit after the registrar's output scaling! There is a check based on the
+/// supply of all configured mints, but do your own checking too.
+///
+/// Vote weights are computed as u128 internally. If the u64 limit forces a choice
+/// between precision and overflow safety, use a larger digit_shift here and a
+/// negative output digit shift with configure_vote_weight_output.
 ///
 /// If you use a single mint, prefer digit_shift=0 and baseline_vote_weight_scaled_factor +
 /// max_extra_lockup_vote_weight_scaled_factor <= 1e9. That way you won't have issues with overflow no
@@ -106,6 +111,10 @@ pub fn configure_voting_mint(
         ),
     };
 
+    // The deposit totals are state, not configuration, and must survive.
+    // The lockup warmup, unlock early and clawback settings are configured
+    // separately and are kept as well.
+    let previous = registrar.voting_mints[idx];
     registrar.voting_mints[idx] = VotingMintConfig {
         mint,
         digit_shift,
@@ -113,8 +122,19 @@ pub fn configure_voting_mint(
         max_extra_lockup_vote_weight_scaled_factor,
         lockup_saturation_secs,
         grant_authority: grant_authority.unwrap_or_default(),
-        reserved1: [0; 7],
-        reserved2: [0; 7],
+        clawback_authority: previous.clawback_authority,
+        baseline_vote_weight_scaled_factor_end: 0,
+        max_extra_lockup_vote_weight_scaled_factor_end: 0,
+        factor_schedule_start_ts: 0,
+        factor_schedule_end_ts: 0,
+        deposited_native_total: previous.deposited_native_total,
+        lockup_weighted_native_total: previous.lockup_weighted_native_total,
+        lockup_warmup_enabled: previous.lockup_warmup_enabled,
+        pre_start_lockup_bonus_scaled_factor: previous.pre_start_lockup_bonus_scaled_factor,
+        unlock_early_penalty_destination: previous.unlock_early_penalty_destination,
+        unlock_early_max_penalty_scaled_factor: previous.unlock_early_max_penalty_scaled_factor,
+        clawback_destination: previous.clawback_destination,
+        reserved1: [0; 6],
     };
 
     // Check for overflow in vote weight
@@ -0,0 +1,68 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+// Remaining accounts must be all the token mints that have registered
+// as voting mints.
+#[derive(Accounts)]
+pub struct ConfigureVotingMintSchedule<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub realm_authority: Signer<'info>,
+
+    /// The already configured voting mint whose factors should change
+    pub mint: Account<'info, Mint>,
+}
+
+/// Schedules a gradual change of the vote weight factors of a voting mint.
+///
+/// * `idx`: index of the voting mint config of `mint`, see Registrar::voting_mint_config_mut
+/// * `baseline_vote_weight_scaled_factor_end`: baseline factor reached at `schedule_end_ts`
+/// * `max_extra_lockup_vote_weight_scaled_factor_end`: max extra lockup factor reached
+///   at `schedule_end_ts`
+/// * `schedule_start_ts`: time at which the factors start changing, must not be
+///   in the past
+/// * `schedule_end_ts`: time at which the end factors are reached
+///
+/// Before `schedule_start_ts` the factors set with configure_voting_mint apply. Between
+/// `schedule_start_ts` and `schedule_end_ts` the factors are interpolated linearly and
+/// after `schedule_end_ts` the end factors apply.
+///
+/// Example: Phasing out a legacy token's vote weight over 6 months can be done by
+/// setting both end factors to zero and `schedule_end_ts` to six months after
+/// `schedule_start_ts`.
+///
+/// The overflow check is done with the largest factors over the whole schedule.
+/// Calling configure_voting_mint again removes the schedule.
+pub fn configure_voting_mint_schedule(
+    ctx: Context<ConfigureVotingMintSchedule>,
+    idx: u16,
+    baseline_vote_weight_scaled_factor_end: u64,
+    max_extra_lockup_vote_weight_scaled_factor_end: u64,
+    schedule_start_ts: i64,
+    schedule_end_ts: i64,
+) -> Result<()> {
+    require_gt!(
+        schedule_end_ts,
+        schedule_start_ts,
+        VsrError::InvalidTimestampArguments
+    );
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    require_gte!(
+        schedule_start_ts,
+        registrar.clock_unix_timestamp(),
+        VsrError::InvalidTimestampArguments
+    );
+    let config = registrar.voting_mint_config_mut(idx, ctx.accounts.mint.key())?;
+    config.baseline_vote_weight_scaled_factor_end = baseline_vote_weight_scaled_factor_end;
+    config.max_extra_lockup_vote_weight_scaled_factor_end =
+        max_extra_lockup_vote_weight_scaled_factor_end;
+    config.factor_schedule_start_ts = schedule_start_ts;
+    config.factor_schedule_end_ts = schedule_end_ts;
+
+    // Check for overflow in vote weight
+    registrar.max_vote_weight(ctx.remaining_accounts)?;
+
+    Ok(())
+}
@@ -55,7 +55,7 @@ pub struct CreateDepositEntry<'info> {
 ///
 /// - `periods`: How long to lock up, depending on `kind`. See LockupKind::period_secs()
 /// - `allow_clawback`: When enabled, the the realm_authority is allowed to
-///    unilaterally claim locked tokens.
+///    unilaterally claim locked tokens. See also configure_clawback_authority.
 pub fn create_deposit_entry(
     ctx: Context<CreateDepositEntry>,
     deposit_entry_index: u8,
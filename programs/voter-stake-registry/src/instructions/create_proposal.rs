@@ -0,0 +1,124 @@
+use crate::instructions::write_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use spl_governance::state::proposal::VoteType;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(has_one = realm, has_one = governance_program_id)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority,
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_weight_record_bump,
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.voter_authority,
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.realm_governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// The spl-governance program of the registrar
+    pub governance_program_id: UncheckedAccount<'info>,
+
+    /// The accounts below are verified by spl-governance
+    pub realm: UncheckedAccount<'info>,
+    pub realm_config: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub governance: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub proposal: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub proposal_owner_record: UncheckedAccount<'info>,
+    pub governing_token_mint: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Refreshes the voter weight record for creating a proposal on `governance`
+/// and creates the proposal through spl-governance in one instruction.
+///
+/// The arguments are passed on to spl-governance's CreateProposal. The record
+/// is written like update_voter_weight_record does for CreateProposal with the
+/// governance as target.
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    name: String,
+    description_link: String,
+    vote_type: VoteType,
+    options: Vec<String>,
+    use_deny_option: bool,
+    proposal_index: u32,
+) -> Result<()> {
+    {
+        let registrar = &ctx.accounts.registrar.load()?;
+        let voter = &mut ctx.accounts.voter.load_mut()?;
+        write_voter_weight_record(
+            registrar,
+            &ctx.accounts.voter.key(),
+            voter,
+            &mut ctx.accounts.voter_weight_record,
+            Some(VoterWeightAction::CreateProposal),
+            Some(ctx.accounts.governance.key()),
+            &[],
+            ctx.program_id,
+        )?;
+    }
+    // spl-governance must see the updated record
+    ctx.accounts.voter_weight_record.exit(ctx.program_id)?;
+
+    let a = &ctx.accounts;
+    let instruction = spl_governance::instruction::create_proposal(
+        a.governance_program_id.key,
+        a.governance.key,
+        a.proposal_owner_record.key,
+        a.voter_authority.key,
+        a.payer.key,
+        Some(a.voter_weight_record.key()),
+        a.realm.key,
+        name,
+        description_link,
+        a.governing_token_mint.key,
+        vote_type,
+        options,
+        use_deny_option,
+        proposal_index,
+    );
+    invoke(
+        &instruction,
+        &[
+            a.realm.to_account_info(),
+            a.proposal.to_account_info(),
+            a.governance.to_account_info(),
+            a.proposal_owner_record.to_account_info(),
+            a.governing_token_mint.to_account_info(),
+            a.voter_authority.to_account_info(),
+            a.payer.to_account_info(),
+            a.system_program.to_account_info(),
+            a.rent.to_account_info(),
+            a.realm_config.to_account_info(),
+            a.voter_weight_record.to_account_info(),
+            a.governance_program_id.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::error::*;
+use crate::instructions::init_voter_weight_checkpoints;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::sysvar::instructions as tx_instructions;
@@ -33,6 +34,16 @@ pub struct CreateVoter<'info> {
     )]
     pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
 
+    /// The history of the voter's vote weight. See create_voter_weight_checkpoints.
+    #[account(
+        init,
+        seeds = [voter.key().as_ref(), b"voter-weight-checkpoints".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<VoterWeightCheckpoints>(),
+    )]
+    pub voter_weight_checkpoints: AccountLoader<'info, VoterWeightCheckpoints>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -48,6 +59,8 @@ pub struct CreateVoter<'info> {
 ///
 /// The user must register with spl-governance using the same voter_authority.
 /// Their token owner record will be required for withdrawing funds later.
+///
+/// The voter's VoterWeightCheckpoints are created along with it.
 pub fn create_voter(
     ctx: Context<CreateVoter>,
     voter_bump: u8,
@@ -82,10 +95,20 @@ pub fn create_voter(
     voter.voter_weight_record_bump = voter_weight_record_bump;
     voter.voter_authority = voter_authority;
     voter.registrar = ctx.accounts.registrar.key();
+    // A new voter has no deposits yet, so it's part of the deposit totals.
+    voter.deposit_totals_epoch = registrar.deposit_totals_epoch;
+    let checkpoints = &mut ctx.accounts.voter_weight_checkpoints.load_init()?;
+    init_voter_weight_checkpoints(
+        registrar,
+        ctx.accounts.voter.key(),
+        voter,
+        checkpoints,
+        *ctx.bumps.get("voter_weight_checkpoints").unwrap(),
+    )?;
 
     let voter_weight_record = &mut ctx.accounts.voter_weight_record;
     voter_weight_record.account_discriminator =
-        spl_governance_addin_api::voter_weight::VoterWeightRecord::ACCOUNT_DISCRIMINATOR;
+        registrar.voter_weight_record_version.discriminator();
     voter_weight_record.realm = registrar.realm;
     voter_weight_record.governing_token_mint = registrar.realm_governing_token_mint;
     voter_weight_record.governing_token_owner = voter_authority;
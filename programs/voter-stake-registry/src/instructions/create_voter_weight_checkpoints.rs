@@ -0,0 +1,71 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightCheckpoints<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(mut, has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        init,
+        seeds = [voter.key().as_ref(), b"voter-weight-checkpoints".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<VoterWeightCheckpoints>(),
+    )]
+    pub voter_weight_checkpoints: AccountLoader<'info, VoterWeightCheckpoints>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Starts keeping a history of the voter's vote weight.
+///
+/// Records the current vote weight as the first checkpoint. Afterwards the
+/// VoterWeightCheckpoints account must be passed, writable, in the remaining
+/// accounts of every instruction that changes the voter's deposits: deposit,
+/// withdraw, grant, fund_grant, revoke_grant, clawback, unlock_early,
+/// reset_lockup and the internal transfers.
+///
+/// create_voter already creates the checkpoints. This is for voters that were
+/// created by grant or grant_batch, or migrated from an older version. Anyone
+/// can call it, the checkpoints only record the voter's weight.
+///
+/// The history can be queried with get_voter_weight_at.
+pub fn create_voter_weight_checkpoints(ctx: Context<CreateVoterWeightCheckpoints>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let checkpoints = &mut ctx.accounts.voter_weight_checkpoints.load_init()?;
+    init_voter_weight_checkpoints(
+        registrar,
+        ctx.accounts.voter.key(),
+        voter,
+        checkpoints,
+        *ctx.bumps.get("voter_weight_checkpoints").unwrap(),
+    )
+}
+
+/// Sets up a new VoterWeightCheckpoints account of `voter`, with the current
+/// vote weight as the first checkpoint.
+pub fn init_voter_weight_checkpoints(
+    registrar: &Registrar,
+    voter_key: Pubkey,
+    voter: &mut Voter,
+    checkpoints: &mut VoterWeightCheckpoints,
+    bump: u8,
+) -> Result<()> {
+    voter.has_voter_weight_checkpoints = true;
+    voter.voter_weight_checkpoints_bump = bump;
+
+    checkpoints.voter = voter_key;
+    checkpoints.bump = bump;
+    checkpoints.push(registrar.clock_unix_timestamp(), voter.weight(registrar)?);
+
+    Ok(())
+}
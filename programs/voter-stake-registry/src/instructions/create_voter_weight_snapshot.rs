@@ -0,0 +1,96 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use spl_governance::state::{governance, proposal};
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightSnapshot<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        seeds = [voter.key().as_ref(), b"voter-weight-checkpoints".as_ref()],
+        bump = voter.load()?.voter_weight_checkpoints_bump,
+    )]
+    pub voter_weight_checkpoints: AccountLoader<'info, VoterWeightCheckpoints>,
+
+    /// An spl-governance governance of the registrar's realm
+    ///
+    /// Verification inline in instruction
+    pub governance: UncheckedAccount<'info>,
+
+    /// An spl-governance proposal of `governance` for the realm governing token mint
+    ///
+    /// Verification inline in instruction
+    pub proposal: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [voter.key().as_ref(), b"voter-weight-snapshot".as_ref(), proposal.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<VoterWeightSnapshot>(),
+    )]
+    pub voter_weight_snapshot: AccountLoader<'info, VoterWeightSnapshot>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Fixes the vote weight of a voter for a proposal.
+///
+/// The weight is the voter's weight at the proposal's voting_at, or its
+/// draft_at if voting has not started, taken from the voter's
+/// VoterWeightCheckpoints, see VoterWeightCheckpoints::voter_weight_at(). Fails
+/// if the checkpoints don't go back that far.
+///
+/// Anyone can create snapshots, and votes with update_voter_weight_record
+/// require one. It writes the snapshot's weight for votes on the proposal, so
+/// depositing after the proposal started doesn't increase the voter's weight
+/// on it. Withdrawing still reduces it, since the written weight is capped at
+/// the current weight for `CastVote`, see configure_voter_weight_action.
+pub fn create_voter_weight_snapshot(ctx: Context<CreateVoterWeightSnapshot>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = ctx.accounts.voter.load()?;
+    let checkpoints = ctx.accounts.voter_weight_checkpoints.load()?;
+
+    governance::get_governance_data_for_realm(
+        &registrar.governance_program_id,
+        &ctx.accounts.governance.to_account_info(),
+        &registrar.realm,
+    )?;
+    let proposal = proposal::get_proposal_data_for_governance_and_governing_mint(
+        &registrar.governance_program_id,
+        &ctx.accounts.proposal.to_account_info(),
+        ctx.accounts.governance.key,
+        &registrar.realm_governing_token_mint,
+    )?;
+    let snapshot_ts = proposal.voting_at.unwrap_or(proposal.draft_at);
+
+    let voter_weight = checkpoints
+        .voter_weight_at(&voter, registrar, snapshot_ts)?
+        .ok_or_else(|| error!(VsrError::NoVoterWeightCheckpointBeforeTimestamp))?;
+
+    let snapshot = &mut ctx.accounts.voter_weight_snapshot.load_init()?;
+    snapshot.registrar = ctx.accounts.registrar.key();
+    snapshot.voter = ctx.accounts.voter.key();
+    snapshot.proposal = ctx.accounts.proposal.key();
+    snapshot.snapshot_ts = snapshot_ts;
+    snapshot.voter_weight = voter_weight;
+    snapshot.bump = *ctx.bumps.get("voter_weight_snapshot").unwrap();
+
+    msg!(
+        "Voter weight snapshot of {} at {} for proposal {}",
+        voter_weight,
+        snapshot_ts,
+        snapshot.proposal
+    );
+
+    Ok(())
+}
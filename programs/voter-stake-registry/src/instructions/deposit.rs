@@ -5,6 +5,7 @@ use anchor_spl::token::{self, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -66,10 +67,25 @@ pub fn deposit(ctx: Context<Deposit>, deposit_entry_index: u8, amount: u64) -> R
         return Ok(());
     }
 
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
 
     let d_entry = voter.active_deposit_mut(deposit_entry_index)?;
+    let d_entry_before = *d_entry;
+
+    // Grants funded over time receive tokens through fund_grant only.
+    require!(
+        !d_entry.is_funded_over_time(),
+        VsrError::InvalidChangeToGrantFundedOverTime
+    );
+
+    // Tokens added to a grant that can still be revoked would be lost to the grantor.
+    let curr_ts = registrar.clock_unix_timestamp();
+    require!(
+        !d_entry.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
 
     // Get the exchange rate entry associated with this deposit.
     let mint_idx = registrar.voting_mint_config_index(ctx.accounts.deposit_token.mint)?;
@@ -87,7 +103,6 @@ pub fn deposit(ctx: Context<Deposit>, deposit_entry_index: u8, amount: u64) -> R
     //   if the lockup forward by the number of expired vesting periods
     // - add the new funds to the locked up token count, so they will vest over
     //   the remaining periods.
-    let curr_ts = registrar.clock_unix_timestamp();
     d_entry.resolve_vesting(curr_ts)?;
 
     // Deposit tokens into the vault and increase the lockup amount too.
@@ -97,6 +112,7 @@ pub fn deposit(ctx: Context<Deposit>, deposit_entry_index: u8, amount: u64) -> R
         .amount_initially_locked_native
         .checked_add(amount)
         .unwrap();
+    deposit_totals.record(&d_entry_before, d_entry);
 
     msg!(
         "Deposited amount {} at deposit index {} with lockup kind {:?} and {} seconds left",
@@ -106,5 +122,14 @@ pub fn deposit(ctx: Context<Deposit>, deposit_entry_index: u8, amount: u64) -> R
         d_entry.lockup.seconds_left(curr_ts),
     );
 
-    Ok(())
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
+
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
@@ -0,0 +1,125 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FundGrant<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter.load()?.voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = deposit_token.mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = deposit_token.owner == token_authority.key(),
+    )]
+    pub deposit_token: Box<Account<'info, TokenAccount>>,
+
+    /// Authority for transfering tokens away from deposit_token
+    pub token_authority: Signer<'info>,
+
+    /// The grantor of the deposit entry, the realm authority or the grant
+    /// authority of the deposit's voting mint
+    ///
+    /// Verification inline in instruction
+    pub grant_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundGrant<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.deposit_token.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.token_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Adds tokens to a grant that is funded over time.
+///
+/// `deposit_entry_index`: The index of the deposit entry created by grant with
+/// `fund_over_time` set.
+/// `amount`: The amount of tokens to add.
+///
+/// The total funded amount can't exceed the part of the promised tokens that
+/// has vested so far. Funded tokens are unlocked immediately.
+pub fn fund_grant(ctx: Context<FundGrant>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+    let registrar = ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
+
+    let d_entry = voter.active_deposit_mut(deposit_entry_index)?;
+    require!(
+        d_entry.is_funded_over_time(),
+        VsrError::GrantNotFundedOverTime
+    );
+    let d_entry_before = *d_entry;
+
+    let mint_idx = d_entry.voting_mint_config_idx as usize;
+    let mint_config = &registrar.voting_mints[mint_idx];
+    require_keys_eq!(
+        mint_config.mint,
+        ctx.accounts.deposit_token.mint,
+        VsrError::InvalidMint
+    );
+
+    let grant_authority = ctx.accounts.grant_authority.key();
+    require!(
+        grant_authority == d_entry.grantor
+            || grant_authority == registrar.realm_authority
+            || grant_authority == mint_config.grant_authority,
+        VsrError::InvalidAuthority
+    );
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    let grant_funded = d_entry.grant_funded_native.checked_add(amount).unwrap();
+    require_gte!(
+        d_entry.grant_vested(curr_ts)?,
+        grant_funded,
+        VsrError::GrantFundingExceedsVested
+    );
+
+    // Deposit the tokens, they are all unlocked.
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+    d_entry.grant_funded_native = grant_funded;
+    d_entry.amount_deposited_native = d_entry.amount_deposited_native.checked_add(amount).unwrap();
+    deposit_totals.record(&d_entry_before, d_entry);
+
+    msg!(
+        "Funded grant with amount {} at deposit index {}, {} of {} funded",
+        amount,
+        deposit_entry_index,
+        grant_funded,
+        d_entry.grant_promised_native,
+    );
+
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
+
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
+}
@@ -0,0 +1,38 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+#[derive(Accounts)]
+pub struct GetVoterWeightAt<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        seeds = [voter.key().as_ref(), b"voter-weight-checkpoints".as_ref()],
+        bump = voter.load()?.voter_weight_checkpoints_bump)]
+    pub voter_weight_checkpoints: AccountLoader<'info, VoterWeightCheckpoints>,
+}
+
+/// A no-effect instruction that returns the voter's vote weight at `ts`.
+///
+/// The weight is returned as a borsh-serialized u64 via set_return_data. Fails
+/// if `ts` is older than the oldest checkpoint that is still kept.
+///
+/// Checkpoints are only written when the deposits change. After the latest one
+/// the returned weight includes the decay of lockups, before it the weight of
+/// the latest checkpoint at or before `ts` is returned, see
+/// VoterWeightCheckpoints::voter_weight_at().
+pub fn get_voter_weight_at(ctx: Context<GetVoterWeightAt>, ts: i64) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &ctx.accounts.voter.load()?;
+    let checkpoints = ctx.accounts.voter_weight_checkpoints.load()?;
+    let weight = checkpoints
+        .voter_weight_at(voter, registrar, ts)?
+        .ok_or_else(|| error!(VsrError::NoVoterWeightCheckpointBeforeTimestamp))?;
+    set_return_data(&weight.try_to_vec()?);
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+
+#[derive(Accounts)]
+pub struct GetVoterWeights<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+}
+
+/// The return data of get_voter_weights.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct VoterWeights {
+    /// The voter's current vote weight, see Voter::weight()
+    pub weight: u64,
+
+    /// The voter's current vote weight ignoring lockups, see Voter::weight_baseline()
+    pub weight_baseline: u64,
+
+    /// The extra lockup vote weight the voter is guaranteed to still have at `at_ts`,
+    /// see Voter::weight_locked_guaranteed()
+    pub weight_locked_guaranteed: u64,
+}
+
+/// A no-effect instruction that computes the voter's vote weights and returns
+/// them as a borsh-serialized VoterWeights via set_return_data.
+///
+/// `at_ts`: The timestamp for the guaranteed locked vote weight, must not be
+/// in the past.
+///
+/// Meant to be called through CPI, so other programs can query the weights
+/// without depending on the account layouts.
+pub fn get_voter_weights(ctx: Context<GetVoterWeights>, at_ts: i64) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = ctx.accounts.voter.load()?;
+    let curr_ts = registrar.clock_unix_timestamp();
+
+    let weights = VoterWeights {
+        weight: voter.weight(registrar)?,
+        weight_baseline: voter.weight_baseline(registrar)?,
+        weight_locked_guaranteed: voter.weight_locked_guaranteed(registrar, curr_ts, at_ts)?,
+    };
+    set_return_data(&weights.try_to_vec()?);
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use std::mem::size_of;
 
 #[derive(Accounts)]
 pub struct Grant<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     #[account(
@@ -90,10 +91,60 @@ pub fn is_freshly_initialized(account_info: &AccountInfo) -> Result<bool> {
     Ok(discriminator == 0)
 }
 
+/// Sets up a new deposit entry on `voter` that locks all of `amount` with `lockup`.
+///
+/// The caller is responsible for transferring the tokens into the voter's vault.
+/// Returns the index of the deposit entry that was used, errors if no free ones
+/// are available.
+///
+/// Grants a voter makes to itself are regular deposits: the grantor is only
+/// recorded if it's not the voter authority.
+///
+/// `deposit_totals` must have been set up for `voter`.
+pub fn add_grant_deposit_entry(
+    deposit_totals: &mut DepositTotalsChange,
+    voter: &mut Voter,
+    mint_idx: usize,
+    grantor: Pubkey,
+    lockup: Lockup,
+    allow_clawback: bool,
+    amount: u64,
+) -> Result<usize> {
+    let is_self_grant = grantor == voter.voter_authority;
+
+    // Get and init the first free deposit entry.
+    let free_entry_idx = voter
+        .deposits
+        .iter()
+        .position(|d_entry| !d_entry.is_used)
+        .ok_or(VsrError::DepositEntryFull)?;
+    let d_entry = &mut voter.deposits[free_entry_idx];
+
+    // Set up a deposit.
+    *d_entry = DepositEntry::default();
+    d_entry.is_used = true;
+    d_entry.voting_mint_config_idx = mint_idx as u8;
+    d_entry.allow_clawback = allow_clawback;
+    if !is_self_grant {
+        d_entry.grantor = grantor;
+    }
+    d_entry.lockup = lockup;
+    d_entry.amount_deposited_native = amount;
+    d_entry.amount_initially_locked_native = amount;
+    deposit_totals.record(&DepositEntry::default(), d_entry);
+
+    Ok(free_entry_idx)
+}
+
 /// Gives a grant to a voter.
 ///
 /// The voter may or may not exist in advance.
 /// Creates a new deposit entry -- errors if no free ones are available.
+///
+/// If `fund_over_time` is set, no tokens are transferred. Instead `amount` is
+/// the total promised to the voter, vesting on the lockup's schedule, and
+/// the tokens are added later with fund_grant. Only funded tokens count for
+/// voting power.
 #[allow(clippy::too_many_arguments)]
 pub fn grant(
     ctx: Context<Grant>,
@@ -104,6 +155,7 @@ pub fn grant(
     periods: u32,
     allow_clawback: bool,
     amount: u64,
+    fund_over_time: bool,
 ) -> Result<()> {
     require_eq!(voter_bump, *ctx.bumps.get("voter").unwrap());
     require_eq!(
@@ -112,12 +164,12 @@ pub fn grant(
     );
 
     // Load accounts.
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter_authority = ctx.accounts.voter_authority.key();
 
     // Get the exchange rate entry associated with this deposit.
     let mint_idx = registrar.voting_mint_config_index(ctx.accounts.deposit_token.mint)?;
-    let mint_config = &registrar.voting_mints[mint_idx];
+    let mint_config = registrar.voting_mints[mint_idx];
 
     // The grant instruction creates a new deposit entry for the target voter. This is a
     // limited resource. If anyone could call "grant" then it could be used for denial of
@@ -142,25 +194,20 @@ pub fn grant(
         voter.voter_weight_record_bump = voter_weight_record_bump;
         voter.voter_authority = voter_authority;
         voter.registrar = ctx.accounts.registrar.key();
+        // A new voter has no deposits yet, so it's part of the deposit totals.
+        voter.deposit_totals_epoch = registrar.deposit_totals_epoch;
 
         // Initializing the voter weight record exactly when setting up the voter is fine.
         // Note that vote_weight_record is not an Anchor account, is_freshly_initialized()
         // would not work.
         let voter_weight_record = &mut ctx.accounts.voter_weight_record;
         voter_weight_record.account_discriminator =
-            spl_governance_addin_api::voter_weight::VoterWeightRecord::ACCOUNT_DISCRIMINATOR;
+            registrar.voter_weight_record_version.discriminator();
         voter_weight_record.realm = registrar.realm;
         voter_weight_record.governing_token_mint = registrar.realm_governing_token_mint;
         voter_weight_record.governing_token_owner = voter_authority;
     }
-
-    // Get and init the first free deposit entry.
-    let free_entry_idx = voter
-        .deposits
-        .iter()
-        .position(|d_entry| !d_entry.is_used)
-        .ok_or(VsrError::DepositEntryFull)?;
-    let d_entry = &mut voter.deposits[free_entry_idx];
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, &voter);
 
     let curr_ts = registrar.clock_unix_timestamp();
     let start_ts = if let Some(v) = start_ts {
@@ -168,26 +215,55 @@ pub fn grant(
     } else {
         curr_ts
     };
+    let lockup = Lockup::new_from_periods(kind, curr_ts, start_ts, periods)?;
 
-    // Set up a deposit.
-    *d_entry = DepositEntry::default();
-    d_entry.is_used = true;
-    d_entry.voting_mint_config_idx = mint_idx as u8;
-    d_entry.allow_clawback = allow_clawback;
-    d_entry.lockup = Lockup::new_from_periods(kind, curr_ts, start_ts, periods)?;
-
-    // Deposit tokens, locking them all.
-    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
-    d_entry.amount_deposited_native = amount;
-    d_entry.amount_initially_locked_native = amount;
+    let free_entry_idx = if fund_over_time {
+        // Constant lockups never vest, so they could never be funded.
+        require!(kind != LockupKind::Constant, VsrError::InvalidLockupKind);
+        // Without a grantor, a voter's promise to itself couldn't be funded.
+        require_keys_neq!(grant_authority, voter_authority, VsrError::InvalidAuthority);
+        let free_entry_idx = add_grant_deposit_entry(
+            &mut deposit_totals,
+            &mut voter,
+            mint_idx,
+            grant_authority,
+            lockup,
+            allow_clawback,
+            0,
+        )?;
+        voter.deposits[free_entry_idx].grant_promised_native = amount;
+        free_entry_idx
+    } else {
+        // Deposit tokens, locking them all.
+        token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+        add_grant_deposit_entry(
+            &mut deposit_totals,
+            &mut voter,
+            mint_idx,
+            grant_authority,
+            lockup,
+            allow_clawback,
+            amount,
+        )?
+    };
 
     msg!(
         "Granted amount {} at deposit index {} with lockup kind {:?} for {} periods",
         amount,
         free_entry_idx,
-        d_entry.lockup.kind,
+        kind,
         periods,
     );
 
-    Ok(())
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        &voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
+
+    drop(voter);
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
@@ -0,0 +1,328 @@
+use crate::error::*;
+use crate::instructions::add_grant_deposit_entry;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::convert::TryFrom;
+use std::mem::size_of;
+
+/// A single recipient of `grant_batch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub struct GrantBatchItem {
+    /// The address controlling the voter that the grant is going to.
+    pub voter_authority: Pubkey,
+    /// The amount of tokens to grant.
+    pub amount: u64,
+}
+
+// Remaining accounts must be the voter, voter weight record and vault
+// accounts for each entry of `grants`, in the same order:
+// [voter_0, voter_weight_record_0, vault_0, voter_1, ...]
+// All of them must be writable. Accounts that don't exist yet are created.
+// They are followed by the writable VoterWeightCheckpoints accounts of the
+// recipients that have them.
+#[derive(Accounts)]
+pub struct GrantBatch<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        mut,
+        constraint = deposit_token.owner == token_authority.key(),
+        constraint = deposit_token.mint == deposit_mint.key(),
+    )]
+    pub deposit_token: Box<Account<'info, TokenAccount>>,
+
+    /// Authority for transfering tokens away from deposit_token
+    pub token_authority: Signer<'info>,
+
+    /// Authority for making grants with this voting mint
+    ///
+    /// Verification inline in instruction
+    pub grant_authority: Signer<'info>,
+
+    /// Rent payer for new accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub deposit_mint: Box<Account<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+impl<'info> GrantBatch<'info> {
+    pub fn transfer_ctx(
+        &self,
+        vault: &AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.deposit_token.to_account_info(),
+            to: vault.clone(),
+            authority: self.token_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+
+    pub fn create_vault_ctx(
+        &self,
+        vault: &AccountInfo<'info>,
+        voter: &AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, associated_token::Create<'info>> {
+        let program = self.associated_token_program.to_account_info();
+        let accounts = associated_token::Create {
+            payer: self.payer.to_account_info(),
+            associated_token: vault.clone(),
+            authority: voter.clone(),
+            mint: self.deposit_mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            rent: self.rent.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+
+    /// Creates a program owned account at the PDA `account`.
+    ///
+    /// Like anchor's `init`, this also works if the address was already funded.
+    pub fn create_pda_account(
+        &self,
+        account: &AccountInfo<'info>,
+        space: usize,
+        seeds: &[&[u8]],
+        program_id: &Pubkey,
+    ) -> Result<()> {
+        let program = self.system_program.to_account_info();
+        let payer = self.payer.to_account_info();
+        let signer_seeds = &[seeds];
+        let rent_lamports = Rent::get()?.minimum_balance(space);
+        let current_lamports = account.lamports();
+        if current_lamports == 0 {
+            let accounts = system_program::CreateAccount {
+                from: payer,
+                to: account.clone(),
+            };
+            system_program::create_account(
+                CpiContext::new_with_signer(program, accounts, signer_seeds),
+                rent_lamports,
+                space as u64,
+                program_id,
+            )
+        } else {
+            let required_lamports = rent_lamports.saturating_sub(current_lamports);
+            if required_lamports > 0 {
+                let accounts = system_program::Transfer {
+                    from: payer,
+                    to: account.clone(),
+                };
+                system_program::transfer(
+                    CpiContext::new(program.clone(), accounts),
+                    required_lamports,
+                )?;
+            }
+            let accounts = system_program::Allocate {
+                account_to_allocate: account.clone(),
+            };
+            system_program::allocate(
+                CpiContext::new_with_signer(program.clone(), accounts, signer_seeds),
+                space as u64,
+            )?;
+            let accounts = system_program::Assign {
+                account_to_assign: account.clone(),
+            };
+            system_program::assign(
+                CpiContext::new_with_signer(program, accounts, signer_seeds),
+                program_id,
+            )
+        }
+    }
+}
+
+/// Gives grants with the same lockup to many voters at once.
+///
+/// Works like `grant` for each entry of `grants`, transferring all tokens from
+/// the single `deposit_token` account. Voters, voter weight records and vaults
+/// that don't exist yet are created.
+///
+/// `grant_authority` must be the realm authority or the grant authority of the
+/// voting mint.
+///
+/// The instruction is atomic: if any recipient has no free deposit entry, no
+/// grant is made.
+pub fn grant_batch<'key, 'accounts, 'remaining, 'info>(
+    ctx: Context<'key, 'accounts, 'remaining, 'info, GrantBatch<'info>>,
+    kind: LockupKind,
+    start_ts: Option<u64>,
+    periods: u32,
+    allow_clawback: bool,
+    grants: Vec<GrantBatchItem>,
+) -> Result<()> {
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        grants.len() * 3,
+        VsrError::InvalidGrantBatchAccounts
+    );
+    let (grant_accounts, checkpoint_accounts) = ctx.remaining_accounts.split_at(grants.len() * 3);
+
+    let registrar_key = ctx.accounts.registrar.key();
+    let registrar = ctx.accounts.registrar.load()?;
+
+    // Get the exchange rate entry associated with this deposit.
+    let deposit_mint = ctx.accounts.deposit_token.mint;
+    let mint_idx = registrar.voting_mint_config_index(deposit_mint)?;
+    let mint_config = registrar.voting_mints[mint_idx];
+
+    // Unlike grant, the voter authority can't give itself grants here.
+    let grant_authority = ctx.accounts.grant_authority.key();
+    require!(
+        grant_authority == registrar.realm_authority
+            || grant_authority == mint_config.grant_authority,
+        VsrError::InvalidAuthority
+    );
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    let start_ts = if let Some(v) = start_ts {
+        i64::try_from(v).unwrap()
+    } else {
+        curr_ts
+    };
+    let lockup = Lockup::new_from_periods(kind, curr_ts, start_ts, periods)?;
+
+    let mut deposit_totals = DepositTotalsChange::default();
+    for (item, accounts) in grants.iter().zip(grant_accounts.chunks(3)) {
+        let voter_authority = item.voter_authority;
+        let voter_info = &accounts[0];
+        let voter_weight_record_info = &accounts[1];
+        let vault_info = &accounts[2];
+
+        let (voter_key, voter_bump) = Pubkey::find_program_address(
+            &[
+                registrar_key.as_ref(),
+                b"voter".as_ref(),
+                voter_authority.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        let (voter_weight_record_key, voter_weight_record_bump) = Pubkey::find_program_address(
+            &[
+                registrar_key.as_ref(),
+                b"voter-weight-record".as_ref(),
+                voter_authority.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        require_keys_eq!(
+            voter_info.key(),
+            voter_key,
+            VsrError::InvalidGrantBatchAccounts
+        );
+        require_keys_eq!(
+            voter_weight_record_info.key(),
+            voter_weight_record_key,
+            VsrError::InvalidGrantBatchAccounts
+        );
+        require_keys_eq!(
+            vault_info.key(),
+            associated_token::get_associated_token_address(&voter_key, &deposit_mint),
+            VsrError::InvalidGrantBatchAccounts
+        );
+
+        // Init the voter and voter weight record if they don't exist yet.
+        let voter_loader = if voter_info.data_is_empty() {
+            ctx.accounts.create_pda_account(
+                voter_info,
+                8 + size_of::<Voter>(),
+                &[
+                    registrar_key.as_ref(),
+                    b"voter".as_ref(),
+                    voter_authority.as_ref(),
+                    &[voter_bump],
+                ],
+                ctx.program_id,
+            )?;
+            ctx.accounts.create_pda_account(
+                voter_weight_record_info,
+                size_of::<VoterWeightRecord>(),
+                &[
+                    registrar_key.as_ref(),
+                    b"voter-weight-record".as_ref(),
+                    voter_authority.as_ref(),
+                    &[voter_weight_record_bump],
+                ],
+                ctx.program_id,
+            )?;
+
+            let voter_loader =
+                AccountLoader::<Voter>::try_from_unchecked(ctx.program_id, voter_info)?;
+            {
+                let mut voter = voter_loader.load_init()?;
+                voter.voter_bump = voter_bump;
+                voter.voter_weight_record_bump = voter_weight_record_bump;
+                voter.voter_authority = voter_authority;
+                voter.registrar = registrar_key;
+                voter.deposit_totals_epoch = registrar.deposit_totals_epoch;
+            }
+            voter_loader.exit(ctx.program_id)?;
+
+            let mut voter_weight_record =
+                Account::<VoterWeightRecord>::try_from_unchecked(voter_weight_record_info)?;
+            voter_weight_record.account_discriminator =
+                registrar.voter_weight_record_version.discriminator();
+            voter_weight_record.realm = registrar.realm;
+            voter_weight_record.governing_token_mint = registrar.realm_governing_token_mint;
+            voter_weight_record.governing_token_owner = voter_authority;
+            voter_weight_record.exit(ctx.program_id)?;
+
+            voter_loader
+        } else {
+            AccountLoader::<Voter>::try_from(voter_info)?
+        };
+
+        if vault_info.data_is_empty() {
+            associated_token::create(ctx.accounts.create_vault_ctx(vault_info, voter_info))?;
+        }
+
+        // Deposit tokens, locking them all.
+        token::transfer(ctx.accounts.transfer_ctx(vault_info), item.amount)?;
+        let mut voter = voter_loader.load_mut()?;
+        deposit_totals.set_voter(&registrar, &voter);
+        let free_entry_idx = add_grant_deposit_entry(
+            &mut deposit_totals,
+            &mut voter,
+            mint_idx,
+            grant_authority,
+            lockup,
+            allow_clawback,
+            item.amount,
+        )?;
+        record_voter_weight_checkpoint(
+            &voter_key,
+            &voter,
+            &registrar,
+            checkpoint_accounts,
+            ctx.program_id,
+        )?;
+
+        msg!(
+            "Granted amount {} to {} at deposit index {}",
+            item.amount,
+            voter_authority,
+            free_entry_idx,
+        );
+    }
+
+    msg!(
+        "Granted to {} voters with lockup kind {:?} for {} periods",
+        grants.len(),
+        kind,
+        periods,
+    );
+
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
+}
@@ -1,9 +1,11 @@
 use crate::error::*;
+use crate::instructions::reset_voter_weight_record_in_accounts;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct InternalTransferLocked<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -31,21 +33,44 @@ pub struct InternalTransferLocked<'info> {
 /// - transfering a small part of a big "constant" lockup deposit entry into a "cliff"
 ///   locked deposit entry to start the unlocking process (reset_lockup could only
 ///   change the whole deposit entry to "cliff")
+///
+/// Like for internal_transfer_unlocked, both deposit entries must have the same
+/// delegate while the voter's weight is locked for votes.
+///
+/// Unless the registrar uses `VoterWeightExpiry::CurrentSlot`, the voter's
+/// VoterWeightRecord must be passed in ctx.remaining_accounts, like for
+/// internal_transfer_unlocked.
 pub fn internal_transfer_locked(
     ctx: Context<InternalTransferLocked>,
     source_deposit_entry_index: u8,
     target_deposit_entry_index: u8,
     amount: u64,
 ) -> Result<()> {
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
     let curr_ts = registrar.clock_unix_timestamp();
+    let has_active_votes = voter.active_votes() > 0;
 
     let source = voter.active_deposit_mut(source_deposit_entry_index)?;
+    let source_before = *source;
+    require!(
+        !source.is_funded_over_time(),
+        VsrError::InvalidChangeToGrantFundedOverTime
+    );
+    require!(
+        !source.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+    require!(
+        !source.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
     source.resolve_vesting(curr_ts)?;
     let source_seconds_left = source.lockup.seconds_left(curr_ts);
     let source_strictness = source.lockup.kind.strictness();
     let source_mint_idx = source.voting_mint_config_idx;
+    let source_delegate = source.delegate;
 
     // Allowing transfers from clawback-enabled deposits could be used to avoid
     // clawback by making proposal instructions target the wrong entry index.
@@ -63,15 +88,38 @@ pub fn internal_transfer_locked(
     source.amount_deposited_native = source.amount_deposited_native.checked_sub(amount).unwrap();
     source.amount_initially_locked_native =
         source.amount_initially_locked_native.saturating_sub(amount);
+    deposit_totals.record(&source_before, source);
 
     // Check target compatibility
     let target = voter.active_deposit_mut(target_deposit_entry_index)?;
+    let target_before = *target;
+    require!(
+        !target.is_funded_over_time(),
+        VsrError::InvalidChangeToGrantFundedOverTime
+    );
+    require!(
+        !target.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+    require!(
+        !target.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
     target.resolve_vesting(curr_ts)?;
     require_eq!(
         target.voting_mint_config_idx,
         source_mint_idx,
         VsrError::InvalidMint
     );
+    // While the weight is locked for votes, moving tokens to an entry with
+    // another delegate would let them vote again.
+    if has_active_votes {
+        require_keys_eq!(
+            target.delegate,
+            source_delegate,
+            VsrError::VoterHasOutstandingVotes
+        );
+    }
     require_gte!(
         target.lockup.seconds_left(curr_ts),
         source_seconds_left,
@@ -89,6 +137,22 @@ pub fn internal_transfer_locked(
         .amount_initially_locked_native
         .checked_add(amount)
         .unwrap();
+    deposit_totals.record(&target_before, target);
+
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
+    reset_voter_weight_record_in_accounts(
+        &registrar,
+        voter,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
 
-    Ok(())
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
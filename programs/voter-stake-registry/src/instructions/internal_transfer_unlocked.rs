@@ -1,9 +1,11 @@
 use crate::error::*;
+use crate::instructions::reset_voter_weight_record_in_accounts;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct InternalTransferUnlocked<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -25,18 +27,37 @@ pub struct InternalTransferUnlocked<'info> {
 /// The primary usecase is moving some deposited funds from one deposit entry to
 /// another because the user wants to lock them, without having to withdraw and re-deposit
 /// them.
+///
+/// While the voter's weight is locked for votes, see release_vote_locks, both
+/// deposit entries must have the same delegate, see set_deposit_entry_delegate.
+///
+/// Unless the registrar uses `VoterWeightExpiry::CurrentSlot`, the voter's
+/// VoterWeightRecord must be passed in ctx.remaining_accounts, writable: moving
+/// tokens can reduce the voter's weight, for example into a delegated deposit entry.
 pub fn internal_transfer_unlocked(
     ctx: Context<InternalTransferUnlocked>,
     source_deposit_entry_index: u8,
     target_deposit_entry_index: u8,
     amount: u64,
 ) -> Result<()> {
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
     let curr_ts = registrar.clock_unix_timestamp();
+    let has_active_votes = voter.active_votes() > 0;
 
     let source = voter.active_deposit_mut(source_deposit_entry_index)?;
+    let source_before = *source;
+    require!(
+        !source.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+    require!(
+        !source.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
     let source_mint_idx = source.voting_mint_config_idx;
+    let source_delegate = source.delegate;
 
     // Reduce source amounts
     require_gte!(
@@ -45,17 +66,52 @@ pub fn internal_transfer_unlocked(
         VsrError::InsufficientUnlockedTokens
     );
     source.amount_deposited_native = source.amount_deposited_native.checked_sub(amount).unwrap();
+    deposit_totals.record(&source_before, source);
 
     // Check target compatibility
     let target = voter.active_deposit_mut(target_deposit_entry_index)?;
+    let target_before = *target;
+    require!(
+        !target.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+    require!(
+        !target.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
     require_eq!(
         target.voting_mint_config_idx,
         source_mint_idx,
         VsrError::InvalidMint
     );
+    // While the weight is locked for votes, moving tokens to an entry with
+    // another delegate would let them vote again.
+    if has_active_votes {
+        require_keys_eq!(
+            target.delegate,
+            source_delegate,
+            VsrError::VoterHasOutstandingVotes
+        );
+    }
 
     // Add target amounts
     target.amount_deposited_native = target.amount_deposited_native.checked_add(amount).unwrap();
+    deposit_totals.record(&target_before, target);
+
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
+    reset_voter_weight_record_in_accounts(
+        &registrar,
+        voter,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
 
-    Ok(())
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
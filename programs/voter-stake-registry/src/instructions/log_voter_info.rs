@@ -69,10 +69,16 @@ pub fn log_voter_info(
             deposit_entry_index: deposit_index as u8,
             voting_mint_config_index: deposit.voting_mint_config_idx,
             unlocked: deposit.amount_unlocked(curr_ts),
-            voting_power: deposit.voting_power(voting_mint_config, curr_ts)?,
-            voting_power_baseline: voting_mint_config
-                .baseline_vote_weight(deposit.amount_deposited_native)?,
+            voting_power: registrar
+                .vote_weight_output(deposit.voting_power(voting_mint_config, curr_ts)?)?,
+            voting_power_baseline: registrar.vote_weight_output(
+                voting_mint_config
+                    .baseline_vote_weight(deposit.amount_deposited_native, curr_ts)?,
+            )?,
             locking: locking_info,
+            grantor: deposit.grantor,
+            lock_delegate: deposit.lock_delegate,
+            delegate: deposit.delegate,
         });
     }
     Ok(())
@@ -0,0 +1,84 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use bytemuck::bytes_of;
+
+#[derive(Accounts)]
+pub struct MigrateRegistrar<'info> {
+    /// A registrar in the layout of v0.2.4 or earlier.
+    ///
+    /// The discriminator and size are validated in the instruction.
+    #[account(mut, owner = crate::id())]
+    pub registrar: UncheckedAccount<'info>,
+
+    /// Pays for the rent of the larger account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Resizes a program owned account to `space` bytes, topping up its
+/// lamports from `payer` to stay rent exempt.
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    let rent_lamports = Rent::get()?.minimum_balance(space);
+    let required_lamports = rent_lamports.saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        let accounts = system_program::Transfer {
+            from: payer.to_account_info(),
+            to: account.clone(),
+        };
+        system_program::transfer(
+            CpiContext::new(system_program.to_account_info(), accounts),
+            required_lamports,
+        )?;
+    }
+    account.realloc(space, false)?;
+    Ok(())
+}
+
+/// Converts a registrar created by v0.2.4 or earlier to the current layout.
+///
+/// The voting mint configs grew, which moved all fields after them. This
+/// instruction grows the account and rewrites the existing fields at their new
+/// offsets. All fields that were added since keep their default values.
+///
+/// Anyone can call this, the registrar's configuration doesn't change. Until it
+/// is migrated, the registrar can't be used with the other instructions.
+pub fn migrate_registrar(ctx: Context<MigrateRegistrar>) -> Result<()> {
+    let account = ctx.accounts.registrar.to_account_info();
+    let legacy_registrar = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() == 8 + std::mem::size_of::<RegistrarV0>()
+                && data[..8] == Registrar::discriminator(),
+            VsrError::InvalidLegacyAccount
+        );
+        RegistrarV0::from_account_data(&data)
+    };
+    let registrar = legacy_registrar.migrate();
+
+    let space = 8 + std::mem::size_of::<Registrar>();
+    resize_account(
+        &account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        space,
+    )?;
+    account.try_borrow_mut_data()?[8..space].copy_from_slice(bytes_of(&registrar));
+
+    msg!(
+        "Migrated registrar {} for realm {}",
+        account.key(),
+        registrar.realm
+    );
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::error::*;
+use crate::instructions::resize_account;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use bytemuck::bytes_of;
+
+#[derive(Accounts)]
+pub struct MigrateVoter<'info> {
+    /// A voter in the layout of v0.2.4 or earlier.
+    ///
+    /// The discriminator and size are validated in the instruction.
+    #[account(mut, owner = crate::id())]
+    pub voter: UncheckedAccount<'info>,
+
+    /// Pays for the rent of the larger account.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Converts a voter created by v0.2.4 or earlier to the current layout.
+///
+/// Deposit entries grew and the voter got new fields. This instruction grows
+/// the account and rewrites each deposit entry at its new offset. All fields
+/// that were added since keep their default values: the voter has no vote
+/// locks, delegations, grantors or lock delegates.
+///
+/// The voter is not part of the registrar's deposit totals. If the registrar
+/// uses `MaxVoteWeightSource::Deposits`, call sync_deposit_totals afterwards.
+///
+/// Anyone can call this, the voter's deposits don't change. Until it is
+/// migrated, the voter can't be used with the other instructions.
+pub fn migrate_voter(ctx: Context<MigrateVoter>) -> Result<()> {
+    let account = ctx.accounts.voter.to_account_info();
+    let legacy_voter = {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() == 8 + std::mem::size_of::<VoterV0>() && data[..8] == Voter::discriminator(),
+            VsrError::InvalidLegacyAccount
+        );
+        VoterV0::from_account_data(&data)
+    };
+    let voter = legacy_voter.migrate();
+
+    let space = 8 + std::mem::size_of::<Voter>();
+    resize_account(
+        &account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        space,
+    )?;
+    account.try_borrow_mut_data()?[8..space].copy_from_slice(bytes_of(&voter));
+
+    msg!(
+        "Migrated voter {} of voter authority {}",
+        account.key(),
+        voter.voter_authority
+    );
+
+    Ok(())
+}
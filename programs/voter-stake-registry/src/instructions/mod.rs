@@ -1,35 +1,101 @@
+pub use cast_vote::*;
 pub use clawback::*;
 pub use close_deposit_entry::*;
 pub use close_voter::*;
+pub use configure_clawback_authority::*;
+pub use configure_clawback_destination::*;
+pub use configure_clawback_policy::*;
+pub use configure_lockup_warmup::*;
+pub use configure_max_vote_weight_source::*;
+pub use configure_supply_exclusions::*;
+pub use configure_unlock_early::*;
+pub use configure_vote_weight_output::*;
+pub use configure_voter_weight_action::*;
+pub use configure_voter_weight_expiry::*;
+pub use configure_voter_weight_record_version::*;
 pub use configure_voting_mint::*;
+pub use configure_voting_mint_schedule::*;
 pub use create_deposit_entry::*;
+pub use create_proposal::*;
 pub use create_registrar::*;
 pub use create_voter::*;
+pub use create_voter_weight_checkpoints::*;
+pub use create_voter_weight_snapshot::*;
 pub use deposit::*;
+pub use fund_grant::*;
+pub use get_voter_weight_at::*;
+pub use get_voter_weights::*;
 pub use grant::*;
+pub use grant_batch::*;
 pub use internal_transfer_locked::*;
 pub use internal_transfer_unlocked::*;
 pub use log_voter_info::*;
+pub use migrate_registrar::*;
+pub use migrate_voter::*;
+pub use release_lock_delegate::*;
+pub use release_vote_locks::*;
+pub use relinquish_vote::*;
 pub use reset_lockup::*;
+pub use revoke_grant::*;
+pub use set_delegate::*;
+pub use set_deposit_entry_delegate::*;
+pub use set_lock_delegate::*;
 pub use set_time_offset::*;
+pub use sync_deposit_totals::*;
+pub use unlock_early::*;
 pub use update_max_vote_weight::*;
 pub use update_voter_weight_record::*;
+pub use update_voter_weight_record_with_delegations::*;
+pub use update_voter_weight_records::*;
 pub use withdraw::*;
 
+mod cast_vote;
 mod clawback;
 mod close_deposit_entry;
 mod close_voter;
+mod configure_clawback_authority;
+mod configure_clawback_destination;
+mod configure_clawback_policy;
+mod configure_lockup_warmup;
+mod configure_max_vote_weight_source;
+mod configure_supply_exclusions;
+mod configure_unlock_early;
+mod configure_vote_weight_output;
+mod configure_voter_weight_action;
+mod configure_voter_weight_expiry;
+mod configure_voter_weight_record_version;
 mod configure_voting_mint;
+mod configure_voting_mint_schedule;
 mod create_deposit_entry;
+mod create_proposal;
 mod create_registrar;
 mod create_voter;
+mod create_voter_weight_checkpoints;
+mod create_voter_weight_snapshot;
 mod deposit;
+mod fund_grant;
+mod get_voter_weight_at;
+mod get_voter_weights;
 mod grant;
+mod grant_batch;
 mod internal_transfer_locked;
 mod internal_transfer_unlocked;
 mod log_voter_info;
+mod migrate_registrar;
+mod migrate_voter;
+mod release_lock_delegate;
+mod release_vote_locks;
+mod relinquish_vote;
 mod reset_lockup;
+mod revoke_grant;
+mod set_delegate;
+mod set_deposit_entry_delegate;
+mod set_lock_delegate;
 mod set_time_offset;
+mod sync_deposit_totals;
+mod unlock_early;
 mod update_max_vote_weight;
 mod update_voter_weight_record;
+mod update_voter_weight_record_with_delegations;
+mod update_voter_weight_records;
 mod withdraw;
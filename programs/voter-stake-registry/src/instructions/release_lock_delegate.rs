@@ -0,0 +1,42 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ReleaseLockDelegate<'info> {
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [voter.load()?.registrar.key().as_ref(), b"voter".as_ref(), voter.load()?.voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// The lock delegate of the deposit entry, usually signing through CPI
+    ///
+    /// Verification inline in instruction
+    pub lock_delegate: Signer<'info>,
+}
+
+/// Removes the lock delegate from a deposit entry, making its tokens movable again.
+///
+/// Must be signed by the lock delegate set with set_lock_delegate.
+pub fn release_lock_delegate(
+    ctx: Context<ReleaseLockDelegate>,
+    deposit_entry_index: u8,
+) -> Result<()> {
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let d = voter.active_deposit_mut(deposit_entry_index)?;
+    require!(
+        d.has_lock_delegate() && d.lock_delegate == ctx.accounts.lock_delegate.key(),
+        VsrError::InvalidAuthority
+    );
+    d.lock_delegate = Pubkey::default();
+
+    msg!(
+        "Released lock delegate on deposit index {}",
+        deposit_entry_index
+    );
+
+    Ok(())
+}
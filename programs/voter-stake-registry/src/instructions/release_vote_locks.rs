@@ -0,0 +1,48 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Remaining accounts must be the spl-governance VoteRecord accounts of the
+// votes to release, see VoteLock::vote_record_address().
+#[derive(Accounts)]
+pub struct ReleaseVoteLocks<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(mut, has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+}
+
+/// Releases the voter's vote locks for votes that are over.
+///
+/// A vote lock is created when the voter's weight is written for CastVote on a
+/// proposal, by update_voter_weight_record or, for delegated weight,
+/// update_voter_weight_record_with_delegations. It blocks withdraws, also
+/// when the vote was cast with the delegate's token owner record.
+///
+/// A lock can be released once the vote was relinquished, or if no vote was
+/// cast with the voter weight record. Anyone can call this.
+pub fn release_vote_locks(ctx: Context<ReleaseVoteLocks>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let curr_slot = Clock::get()?.slot;
+
+    for vote_record_info in ctx.remaining_accounts.iter() {
+        let vote_locks = voter.vote_locks;
+        let index = vote_locks
+            .iter()
+            .position(|l| l.is_used() && l.vote_record_address(registrar) == vote_record_info.key())
+            .ok_or_else(|| error!(VsrError::VoteLockNotReleasable))?;
+        require!(
+            vote_locks[index].is_releasable(vote_record_info, registrar, curr_slot)?,
+            VsrError::VoteLockNotReleasable
+        );
+        voter.vote_locks[index] = VoteLock::default();
+
+        msg!(
+            "Released vote lock for proposal {}",
+            vote_locks[index].proposal
+        );
+    }
+
+    Ok(())
+}
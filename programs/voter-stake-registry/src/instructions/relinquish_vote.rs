@@ -0,0 +1,88 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+
+#[derive(Accounts)]
+pub struct RelinquishVote<'info> {
+    #[account(has_one = governance_program_id)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority,
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The spl-governance program of the registrar
+    pub governance_program_id: UncheckedAccount<'info>,
+
+    /// The token owner record of voter_authority
+    ///
+    /// Verification inline in instruction
+    #[account(mut)]
+    pub voter_token_owner_record: UncheckedAccount<'info>,
+
+    /// The accounts below are verified by spl-governance
+    pub governance: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub proposal: UncheckedAccount<'info>,
+    pub governing_token_mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vote_record: UncheckedAccount<'info>,
+
+    /// Receives the lamports of the vote record if spl-governance closes it
+    #[account(mut)]
+    pub beneficiary: UncheckedAccount<'info>,
+}
+
+/// Relinquishes the voter's vote on `proposal` through spl-governance and
+/// releases the voter's vote lock for it.
+///
+/// Vote locks of delegators are released with release_vote_locks.
+pub fn relinquish_vote(ctx: Context<RelinquishVote>) -> Result<()> {
+    {
+        let registrar = &ctx.accounts.registrar.load()?;
+        let voter = ctx.accounts.voter.load()?;
+        voter.load_token_owner_record(
+            &ctx.accounts.voter_token_owner_record.to_account_info(),
+            registrar,
+        )?;
+    }
+
+    let a = &ctx.accounts;
+    let instruction = spl_governance::instruction::relinquish_vote(
+        a.governance_program_id.key,
+        a.governance.key,
+        a.proposal.key,
+        a.voter_token_owner_record.key,
+        a.governing_token_mint.key,
+        Some(a.voter_authority.key()),
+        Some(a.beneficiary.key()),
+    );
+    invoke(
+        &instruction,
+        &[
+            a.governance.to_account_info(),
+            a.proposal.to_account_info(),
+            a.voter_token_owner_record.to_account_info(),
+            a.vote_record.to_account_info(),
+            a.governing_token_mint.to_account_info(),
+            a.voter_authority.to_account_info(),
+            a.beneficiary.to_account_info(),
+            a.governance_program_id.to_account_info(),
+        ],
+    )?;
+
+    // the vote of voter_authority's token owner record was relinquished
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let voter_authority = voter.voter_authority;
+    voter.remove_vote_lock(ctx.accounts.proposal.key(), voter_authority);
+
+    Ok(())
+}
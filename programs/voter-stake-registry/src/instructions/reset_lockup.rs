@@ -4,6 +4,7 @@ use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct ResetLockup<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -27,8 +28,9 @@ pub fn reset_lockup(
     kind: LockupKind,
     periods: u32,
 ) -> Result<()> {
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
     let curr_ts = registrar.clock_unix_timestamp();
 
     let source = voter.active_deposit_mut(deposit_entry_index)?;
@@ -51,10 +53,38 @@ pub fn reset_lockup(
         VsrError::InvalidChangeToClawbackDepositEntry
     );
 
+    // The lockup of grants funded over time is their vesting schedule.
+    require!(
+        !source.is_funded_over_time(),
+        VsrError::InvalidChangeToGrantFundedOverTime
+    );
+
+    require!(
+        !source.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+
+    // Starting the lockup early would prevent the grantor from revoking it.
+    require!(
+        !source.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
+
     // Change the deposit entry.
     let d_entry = voter.active_deposit_mut(deposit_entry_index)?;
+    let d_entry_before = *d_entry;
     d_entry.amount_initially_locked_native = d_entry.amount_deposited_native;
     d_entry.lockup = Lockup::new_from_periods(kind, curr_ts, curr_ts, periods)?;
+    deposit_totals.record(&d_entry_before, d_entry);
+
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
 
-    Ok(())
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
@@ -0,0 +1,164 @@
+use crate::error::*;
+use crate::instructions::reset_voter_weight_record_in_accounts;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct RevokeGrant<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// The authority that made the grant
+    ///
+    /// Verification inline in instruction
+    pub grantor: Signer<'info>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter.load()?.voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// The token_owner_record for the voter's voter_authority. Only checked
+    /// with `ClawbackPolicy::RequireNoOutstandingVotes`, like for clawback.
+    ///
+    /// token_owner_record is validated in the instruction:
+    /// - owned by registrar.governance_program_id
+    /// - for the registrar.realm
+    /// - for the registrar.realm_governing_token_mint
+    /// - governing_token_owner is the voter's voter_authority
+    pub token_owner_record: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = destination.mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> RevokeGrant<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.vault.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.voter.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Revokes a grant whose lockup has not started yet.
+///
+/// `deposit_entry_index`: The index of the deposit entry created by the grant.
+///
+/// Only the authority that made the grant can revoke it, and only while the
+/// current time is before the lockup's start_ts. All tokens of the deposit
+/// entry are transferred to `destination` and the deposit entry is freed.
+///
+/// Votes of the voter are handled like for clawback: by default they don't
+/// block revoking, so the grantee can't prevent the revocation by voting.
+/// Votes that were already cast keep the weight of the revoked grant. With
+/// `ClawbackPolicy::RequireNoOutstandingVotes`, revoking grants with vote weight
+/// fails while the voter has unrelinquished votes or vote locks.
+///
+/// Unless the registrar uses `VoterWeightExpiry::CurrentSlot`, the voter's
+/// VoterWeightRecord must be passed in ctx.remaining_accounts, like for clawback.
+pub fn revoke_grant(ctx: Context<RevokeGrant>, deposit_entry_index: u8) -> Result<()> {
+    let (amount, deposit_totals) = {
+        let registrar = &ctx.accounts.registrar.load()?;
+        let voter = &mut ctx.accounts.voter.load_mut()?;
+        let mut deposit_totals = DepositTotalsChange::new(registrar, voter);
+
+        let deposit_entry = voter.active_deposit_mut(deposit_entry_index)?;
+        require!(
+            deposit_entry.is_grant() && deposit_entry.grantor == ctx.accounts.grantor.key(),
+            VsrError::InvalidAuthority
+        );
+        require!(
+            !deposit_entry.has_lock_delegate(),
+            VsrError::DepositEntryHasLockDelegate
+        );
+
+        let mint_idx = deposit_entry.voting_mint_config_idx as usize;
+        require_keys_eq!(
+            registrar.voting_mints[mint_idx].mint,
+            ctx.accounts.destination.mint,
+            VsrError::InvalidMint
+        );
+
+        let curr_ts = registrar.clock_unix_timestamp();
+        require_gt!(
+            deposit_entry.lockup.start_ts,
+            curr_ts,
+            VsrError::GrantAlreadyStarted
+        );
+
+        if registrar.clawback_policy == ClawbackPolicy::RequireNoOutstandingVotes
+            && registrar.voting_mints[mint_idx].grants_vote_weight()
+        {
+            let token_owner_record = voter.load_token_owner_record(
+                &ctx.accounts.token_owner_record.to_account_info(),
+                registrar,
+            )?;
+            require_eq!(
+                token_owner_record.unrelinquished_votes_count,
+                0,
+                VsrError::VoterHasOutstandingVotes
+            );
+            voter.assert_no_active_votes()?;
+        }
+
+        let deposit_entry = voter.active_deposit_mut(deposit_entry_index)?;
+        let deposit_entry_before = *deposit_entry;
+        *deposit_entry = DepositEntry::default();
+        deposit_entry.is_used = false;
+        deposit_totals.record(&deposit_entry_before, deposit_entry);
+
+        record_voter_weight_checkpoint(
+            &ctx.accounts.voter.key(),
+            voter,
+            registrar,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+        reset_voter_weight_record_in_accounts(
+            registrar,
+            voter,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        (deposit_entry_before.amount_deposited_native, deposit_totals)
+    };
+    deposit_totals.apply(&ctx.accounts.registrar)?;
+
+    {
+        // Return the granted tokens.
+        let voter = &ctx.accounts.voter.load()?;
+        let voter_seeds = voter_seeds!(voter);
+        token::transfer(
+            ctx.accounts.transfer_ctx().with_signer(&[voter_seeds]),
+            amount,
+        )?;
+    }
+
+    msg!(
+        "Revoked grant of amount {} at deposit index {}",
+        amount,
+        deposit_entry_index,
+    );
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use crate::error::*;
+use crate::instructions::reset_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority)]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// Delegating reduces the voter's own weight, so the voter weight record
+    /// is updated.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_weight_record_bump,
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.voter_authority,
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.realm_governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+}
+
+/// Delegates the voter's vote weight to another voter, or revokes the delegation.
+///
+/// `delegate`: The voter authority of the voter to delegate to, or
+///   Pubkey::default() to stop delegating.
+///
+/// While delegating, update_voter_weight_record writes zero weight for this
+/// voter and the delegate can include the voter's weight with
+/// update_voter_weight_record_with_delegations. Withdraws then also require
+/// that the delegate has no outstanding votes.
+///
+/// The delegation can only be changed or revoked while the voter's weight is
+/// not locked for any vote, see release_vote_locks. Otherwise the weight could
+/// be used again by the new delegate or the voter itself.
+pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    require!(delegate != voter.voter_authority, VsrError::InvalidDelegate);
+    voter.assert_no_active_votes()?;
+    voter.delegate = delegate;
+
+    reset_voter_weight_record(registrar, voter, &mut ctx.accounts.voter_weight_record)?;
+
+    msg!("Set delegate to {}", delegate);
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::error::*;
+use crate::instructions::reset_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDepositEntryDelegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority)]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// Delegating reduces the voter's own weight, so the voter weight record
+    /// is updated.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_weight_record_bump,
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.voter_authority,
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.realm_governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+}
+
+/// Delegates the vote weight of a single deposit entry to another voter, or
+/// revokes the delegation.
+///
+/// `deposit_entry_index`: The deposit entry to delegate.
+/// `delegate`: The address of the Voter account to delegate to, or
+///   Pubkey::default() to stop delegating.
+///
+/// The entry then no longer counts for this voter's weight. The delegate can
+/// include it with update_voter_weight_record_with_delegations. Withdraws
+/// from the entry also require that the delegate has no outstanding votes.
+///
+/// Like set_delegate, this can only be changed while the voter's weight is not
+/// locked for any vote, see release_vote_locks.
+pub fn set_deposit_entry_delegate(
+    ctx: Context<SetDepositEntryDelegate>,
+    deposit_entry_index: u8,
+    delegate: Pubkey,
+) -> Result<()> {
+    let voter_key = ctx.accounts.voter.key();
+    require!(delegate != voter_key, VsrError::InvalidDelegate);
+
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    voter.assert_no_active_votes()?;
+    let d = voter.active_deposit_mut(deposit_entry_index)?;
+    d.delegate = delegate;
+
+    reset_voter_weight_record(registrar, voter, &mut ctx.accounts.voter_weight_record)?;
+
+    msg!(
+        "Set delegate {} on deposit index {}",
+        delegate,
+        deposit_entry_index
+    );
+
+    Ok(())
+}
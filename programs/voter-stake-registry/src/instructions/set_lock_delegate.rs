@@ -0,0 +1,65 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetLockDelegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority)]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+}
+
+/// Locks a deposit entry in place on behalf of `lock_delegate`.
+///
+/// While a lock delegate is set, the deposit entry can't be withdrawn from,
+/// transferred from or to, relocked, unlocked early or closed. That allows other
+/// programs to use it as collateral or as proof of locked tokens, for example
+/// together with the voter's guaranteed locked vote weight.
+///
+/// Only the lock delegate can remove the lock again, see release_lock_delegate.
+///
+/// Tokens can still be added with deposit.
+///
+/// Deposit entries that allow clawback and grants that can still be revoked
+/// can't get a lock delegate, so that a voter can't use a lock to block them
+/// from being taken back.
+pub fn set_lock_delegate(
+    ctx: Context<SetLockDelegate>,
+    deposit_entry_index: u8,
+    lock_delegate: Pubkey,
+) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let curr_ts = registrar.clock_unix_timestamp();
+    let d = voter.active_deposit_mut(deposit_entry_index)?;
+    require!(
+        !d.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
+    require!(
+        !d.allow_clawback,
+        VsrError::LockDelegateNotAllowedOnClawbackDeposit
+    );
+    require!(
+        !d.is_revocable_grant(curr_ts),
+        VsrError::InvalidChangeToRevocableGrant
+    );
+    d.lock_delegate = lock_delegate;
+
+    msg!(
+        "Set lock delegate {} on deposit index {}",
+        lock_delegate,
+        deposit_entry_index
+    );
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SyncDepositTotals<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(mut, has_one = registrar)]
+    pub voter: AccountLoader<'info, Voter>,
+}
+
+/// Adds the deposits of a voter to the registrar's deposit totals.
+///
+/// With `MaxVoteWeightSource::Deposits`, voters that existed before the source
+/// was selected are not part of the totals until this is called for them.
+/// Anyone can call this, it does nothing for voters that are already counted.
+pub fn sync_deposit_totals(ctx: Context<SyncDepositTotals>) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    require!(
+        registrar.max_vote_weight_source == MaxVoteWeightSource::Deposits,
+        VsrError::DepositTotalsNotTracked
+    );
+    if voter.deposit_totals_epoch == registrar.deposit_totals_epoch {
+        return Ok(());
+    }
+
+    let mut deposit_totals = DepositTotalsChange::default();
+    deposit_totals.record_all(voter);
+    deposit_totals.apply_to(registrar)?;
+    voter.deposit_totals_epoch = registrar.deposit_totals_epoch;
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::error::*;
+use crate::instructions::reset_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct UnlockEarly<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_bump,
+        has_one = registrar,
+        has_one = voter_authority,
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. This is needed
+    /// to be able to forbid unlocking while the voter is engaged with
+    /// a vote or has an open proposal.
+    ///
+    /// token_owner_record is validated in the instruction:
+    /// - owned by registrar.governance_program_id
+    /// - for the registrar.realm
+    /// - for the registrar.realm_governing_token_mint
+    /// - governing_token_owner is voter_authority
+    pub token_owner_record: UncheckedAccount<'info>,
+
+    /// Unlocking early must update the voter weight record, to prevent a stale
+    /// record being used to vote afterwards.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.voter_weight_record_bump,
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.voter_authority,
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.realm_governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = destination.mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    /// Must be the voting mint's unlock_early_penalty_destination.
+    #[account(mut)]
+    pub penalty_destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> UnlockEarly<'info> {
+    pub fn transfer_ctx(
+        &self,
+        to: &Account<'info, TokenAccount>,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.vault.to_account_info(),
+            to: to.to_account_info(),
+            authority: self.voter.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Withdraws locked tokens before their lockup ends, paying a penalty.
+///
+/// `deposit_entry_index`: The deposit entry to unlock tokens from.
+/// `amount`: The amount of locked tokens to unlock, in native units. The penalty
+///   is taken out of it, `destination` receives the rest.
+///
+/// Only possible for Cliff and Constant lockups on deposit entries that don't
+/// allow clawback and aren't grants that can still be revoked, and only if the
+/// voting mint has an unlock early penalty destination configured with
+/// configure_unlock_early.
+///
+/// The penalty is the voting mint's unlock_early_max_penalty_scaled_factor,
+/// scaled by min(lockup seconds left, lockup_saturation_secs) / lockup_saturation_secs.
+/// It is sent to the voting mint's unlock_early_penalty_destination.
+///
+/// Delegates must be passed in ctx.remaining_accounts like for withdraw.
+pub fn unlock_early(ctx: Context<UnlockEarly>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+    let (penalty, payout, deposit_totals) = {
+        // Load the accounts.
+        let registrar = &ctx.accounts.registrar.load()?;
+        let voter = &mut ctx.accounts.voter.load_mut()?;
+        let mut deposit_totals = DepositTotalsChange::new(registrar, voter);
+
+        let mint_idx = registrar.voting_mint_config_index(ctx.accounts.destination.mint)?;
+        let mint_config = registrar.voting_mints[mint_idx];
+        require!(
+            mint_config.unlock_early_enabled(),
+            VsrError::UnlockEarlyDisabled
+        );
+        require_keys_eq!(
+            ctx.accounts.penalty_destination.key(),
+            mint_config.unlock_early_penalty_destination,
+            VsrError::InvalidPenaltyDestination
+        );
+
+        // Governance may forbid withdraws, for example when engaged in a vote.
+        // Not applicable for tokens that don't contribute to voting power.
+        if mint_config.grants_vote_weight() {
+            let token_owner_record = voter.load_token_owner_record(
+                &ctx.accounts.token_owner_record.to_account_info(),
+                registrar,
+            )?;
+            token_owner_record.assert_can_withdraw_governing_tokens()?;
+            voter.assert_delegate_can_withdraw(ctx.remaining_accounts, registrar)?;
+            voter.assert_entry_delegate_can_withdraw(
+                deposit_entry_index,
+                ctx.remaining_accounts,
+                registrar,
+            )?;
+            voter.assert_no_active_votes()?;
+        }
+
+        let curr_ts = registrar.clock_unix_timestamp();
+        let deposit_entry = voter.active_deposit_mut(deposit_entry_index)?;
+        require_eq!(
+            mint_idx,
+            deposit_entry.voting_mint_config_idx as usize,
+            VsrError::InvalidMint
+        );
+        require!(
+            !deposit_entry.allow_clawback,
+            VsrError::UnlockEarlyNotAllowedOnClawbackDeposit
+        );
+        require!(
+            !deposit_entry.has_lock_delegate(),
+            VsrError::DepositEntryHasLockDelegate
+        );
+        require!(
+            !deposit_entry.is_revocable_grant(curr_ts),
+            VsrError::InvalidChangeToRevocableGrant
+        );
+        require!(
+            matches!(
+                deposit_entry.lockup.kind,
+                LockupKind::Cliff | LockupKind::Constant
+            ),
+            VsrError::InvalidLockupKind
+        );
+        require_gte!(
+            deposit_entry.amount_locked(curr_ts),
+            amount,
+            VsrError::InsufficientLockedTokens
+        );
+
+        // Bookkeeping for the unlocked funds, like a withdraw of
+        // tokens that were never locked.
+        let deposit_entry_before = *deposit_entry;
+        deposit_entry.amount_deposited_native = deposit_entry
+            .amount_deposited_native
+            .checked_sub(amount)
+            .unwrap();
+        deposit_entry.amount_initially_locked_native = deposit_entry
+            .amount_initially_locked_native
+            .checked_sub(amount)
+            .unwrap();
+        deposit_totals.record(&deposit_entry_before, deposit_entry);
+
+        let seconds_left = deposit_entry.lockup.seconds_left(curr_ts);
+        let penalty = mint_config.unlock_early_penalty(amount, seconds_left);
+        let payout = amount.checked_sub(penalty).unwrap();
+
+        msg!(
+            "Unlocked amount {} early at deposit index {} with {} seconds left, penalty {}",
+            amount,
+            deposit_entry_index,
+            seconds_left,
+            penalty,
+        );
+
+        // Update the voter weight record, valid for any action
+        reset_voter_weight_record(registrar, voter, &mut ctx.accounts.voter_weight_record)?;
+
+        record_voter_weight_checkpoint(
+            &ctx.accounts.voter.key(),
+            voter,
+            registrar,
+            ctx.remaining_accounts,
+            ctx.program_id,
+        )?;
+
+        (penalty, payout, deposit_totals)
+    };
+    deposit_totals.apply(&ctx.accounts.registrar)?;
+
+    // Transfer the tokens.
+    let voter = ctx.accounts.voter.load()?;
+    let voter_seeds = voter_seeds!(voter);
+    if payout > 0 {
+        token::transfer(
+            ctx.accounts
+                .transfer_ctx(&ctx.accounts.destination)
+                .with_signer(&[voter_seeds]),
+            payout,
+        )?;
+    }
+    if penalty > 0 {
+        token::transfer(
+            ctx.accounts
+                .transfer_ctx(&ctx.accounts.penalty_destination)
+                .with_signer(&[voter_seeds]),
+            penalty,
+        )?;
+    }
+
+    Ok(())
+}
@@ -1,8 +1,12 @@
+use crate::error::*;
+use crate::events::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
 // Remaining accounts should all the token mints that have registered
-// exchange rates.
+// exchange rates. If the registrar has supply exclusions, the
+// SupplyExclusions account and all excluded token accounts must be
+// passed as well.
 #[derive(Accounts)]
 pub struct UpdateMaxVoteWeight<'info> {
     pub registrar: AccountLoader<'info, Registrar>,
@@ -14,14 +18,36 @@ pub struct UpdateMaxVoteWeight<'info> {
 /// of the total supply of all exchange rate mints, converted into a
 /// common currency with a common number of decimals.
 ///
+/// Balances of token accounts configured with configure_supply_exclusions
+/// are not counted.
+///
+/// If the registrar uses MaxVoteWeightSource::Deposits, the max vote weight is
+/// computed from the deposited amounts instead and no remaining accounts are needed.
+/// Deposits of voters that weren't synced with sync_deposit_totals yet are not
+/// included.
+///
 /// Note that this method is only safe to use if the cumulative supply for
-/// all tokens fits into a u64 *after* converting into common decimals, as
-/// defined by the registrar's `rate_decimal` field.
+/// all tokens fits into a u64 *after* converting into common decimals and
+/// applying the registrar's vote weight output scaling.
 pub fn update_max_vote_weight(ctx: Context<UpdateMaxVoteWeight>) -> Result<()> {
     let registrar = &ctx.accounts.registrar.load()?;
-    let _max_vote_weight = registrar.max_vote_weight(ctx.remaining_accounts)?;
+    let max_vote_weight = if registrar.max_vote_weight_source == MaxVoteWeightSource::Deposits {
+        registrar.max_vote_weight_from_deposits()?
+    } else if registrar.supply_exclusions == Pubkey::default() {
+        registrar.max_vote_weight(ctx.remaining_accounts)?
+    } else {
+        let exclusions_info = ctx
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == registrar.supply_exclusions)
+            .ok_or_else(|| error!(VsrError::SupplyExclusionAccountNotFound))?;
+        let exclusions_loader = AccountLoader::<SupplyExclusions>::try_from(exclusions_info)?;
+        let exclusions = exclusions_loader.load()?;
+        registrar.max_vote_weight_circulating(ctx.remaining_accounts, &exclusions)?
+    };
     // TODO: SPL governance has not yet implemented this feature.
     //       When it has, probably need to write the result into an account,
     //       similar to VoterWeightRecord.
+    emit!(MaxVoteWeightInfo { max_vote_weight });
     Ok(())
 }
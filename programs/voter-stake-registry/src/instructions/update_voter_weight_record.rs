@@ -1,6 +1,12 @@
+use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
+use spl_governance::state::{governance, proposal};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
 
+// Remaining accounts: for CastVote, the voter's VoterWeightSnapshot for the
+// target proposal, the proposal's governance, the proposal and the voter
+// authority as signer.
 #[derive(Accounts)]
 pub struct UpdateVoterWeightRecord<'info> {
     pub registrar: AccountLoader<'info, Registrar>,
@@ -8,6 +14,7 @@ pub struct UpdateVoterWeightRecord<'info> {
     // checking the PDA address it just an extra precaution,
     // the other constraints must be exhaustive
     #[account(
+        mut,
         seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter.load()?.voter_authority.key().as_ref()],
         bump = voter.load()?.voter_bump,
         has_one = registrar)]
@@ -30,14 +37,272 @@ pub struct UpdateVoterWeightRecord<'info> {
 /// voter and writes it into a `VoteWeightRecord` account to be used by
 /// the SPL governance program.
 ///
-/// This "revise" instruction must be called immediately before voting, in
-/// the same transaction.
-pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
+/// `weight_action`: The governance action the record will be used for. The
+///   registrar's rule for the action decides which deposits count, see
+///   configure_voter_weight_action. Required: spl-governance accepts records
+///   without an action for any action, including votes.
+/// `weight_action_target`: The account the action targets, for example the
+///   proposal for CastVote. If None, the record is valid for any target.
+///   Required for CastVote.
+///
+/// For CastVote, the voter's VoterWeightSnapshot for the proposal, the
+/// proposal's governance, the proposal and the voter authority, signing, must
+/// be passed in ctx.remaining_accounts, in that order. The snapshot must have
+/// been created with create_voter_weight_snapshot. Its weight is written
+/// instead of the current one, unless the current weight is lower. That way
+/// tokens deposited after the proposal started don't count, and tokens
+/// withdrawn after it started can't vote twice.
+///
+/// For CastVote, the voter's weight is also locked for the proposal: withdraws
+/// are blocked until the vote is relinquished, see release_vote_locks. Since
+/// locks block the voter's withdraws, the voter authority must sign and the
+/// target must be a proposal of the realm that can currently be voted on.
+///
+/// While the voter delegates, the written weight is zero: the delegate uses it
+/// through update_voter_weight_record_with_delegations.
+///
+/// With the default `VoterWeightExpiry::CurrentSlot` this "revise" instruction
+/// must be called immediately before voting, in the same transaction. See
+/// configure_voter_weight_expiry for longer lived records.
+pub fn update_voter_weight_record(
+    ctx: Context<UpdateVoterWeightRecord>,
+    weight_action: Option<VoterWeightAction>,
+    weight_action_target: Option<Pubkey>,
+) -> Result<()> {
     let registrar = &ctx.accounts.registrar.load()?;
-    let voter = ctx.accounts.voter.load()?;
-    let record = &mut ctx.accounts.voter_weight_record;
-    record.voter_weight = voter.weight(registrar)?;
-    record.voter_weight_expiry = Some(Clock::get()?.slot);
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    write_voter_weight_record(
+        registrar,
+        &ctx.accounts.voter.key(),
+        voter,
+        &mut ctx.accounts.voter_weight_record,
+        weight_action,
+        weight_action_target,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )
+}
+
+/// Checks that `proposal_info` is a proposal of a governance of the registrar's
+/// realm, for the realm governing token mint, that can currently be voted on.
+///
+/// Vote locks are only added for such proposals, so that voters can't fill
+/// their delegators' vote locks with made up targets.
+pub fn assert_votable_proposal(
+    registrar: &Registrar,
+    governance_info: &AccountInfo,
+    proposal_info: &AccountInfo,
+) -> Result<()> {
+    let governance = governance::get_governance_data_for_realm(
+        &registrar.governance_program_id,
+        governance_info,
+        &registrar.realm,
+    )?;
+    let proposal = proposal::get_proposal_data_for_governance_and_governing_mint(
+        &registrar.governance_program_id,
+        proposal_info,
+        governance_info.key,
+        &registrar.realm_governing_token_mint,
+    )?;
+    proposal
+        .assert_can_cast_vote(&governance.config, Clock::get()?.unix_timestamp)
+        .map_err(|_| error!(VsrError::InvalidVoteProposal))
+}
 
+/// Checks that `authority_info` is the voter's authority and signed.
+///
+/// Vote locks block the voter's withdraws, so they are only added with the
+/// voter authority's signature.
+pub fn assert_voter_authority_signer(voter: &Voter, authority_info: &AccountInfo) -> Result<()> {
+    require!(
+        authority_info.is_signer && authority_info.key() == voter.voter_authority,
+        VsrError::InvalidAuthority
+    );
+    Ok(())
+}
+
+/// Checks that a record for `weight_action` and `weight_action_target` can
+/// only be used for votes with a snapshot and a vote lock.
+///
+/// spl-governance accepts records without an action, or for CastVote without a
+/// target, for votes on any proposal.
+pub fn assert_voter_weight_action(
+    weight_action: &Option<VoterWeightAction>,
+    weight_action_target: Option<Pubkey>,
+) -> Result<()> {
+    require!(
+        !matches!(
+            (weight_action, weight_action_target),
+            (None, _) | (Some(VoterWeightAction::CastVote), None)
+        ),
+        VsrError::InvalidVoterWeightAction
+    );
     Ok(())
 }
+
+/// Checks the accounts for a vote of the voter on `proposal` and returns the
+/// weight of the voter's VoterWeightSnapshot for it.
+///
+/// `vote_accounts` must start with the VoterWeightSnapshot, the proposal's
+/// governance, the proposal and the voter authority, signing.
+pub fn load_vote_snapshot_weight(
+    registrar: &Registrar,
+    voter_key: &Pubkey,
+    voter: &Voter,
+    proposal: Pubkey,
+    vote_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<u64> {
+    let (snapshot_info, governance_info, proposal_info, authority_info) = match vote_accounts {
+        [snapshot_info, governance_info, proposal_info, authority_info, ..] => (
+            snapshot_info,
+            governance_info,
+            proposal_info,
+            authority_info,
+        ),
+        _ => return Err(error!(VsrError::InvalidVoteProposal)),
+    };
+    assert_voter_authority_signer(voter, authority_info)?;
+    require_keys_eq!(proposal_info.key(), proposal, VsrError::InvalidVoteProposal);
+    assert_votable_proposal(registrar, governance_info, proposal_info)?;
+
+    let (address, _) = Pubkey::find_program_address(
+        &[
+            voter_key.as_ref(),
+            b"voter-weight-snapshot".as_ref(),
+            proposal.as_ref(),
+        ],
+        program_id,
+    );
+    require_keys_eq!(
+        snapshot_info.key(),
+        address,
+        VsrError::InvalidVoterWeightSnapshot
+    );
+    require_keys_eq!(
+        *snapshot_info.owner,
+        *program_id,
+        VsrError::InvalidVoterWeightSnapshot
+    );
+    let snapshot = AccountLoader::<VoterWeightSnapshot>::try_from(snapshot_info)?;
+    let voter_weight = snapshot.load()?.voter_weight;
+    Ok(voter_weight)
+}
+
+/// Writes the voter's weight into `record`, like update_voter_weight_record.
+///
+/// For CastVote, `vote_accounts` must start with the VoterWeightSnapshot, the
+/// proposal's governance, the proposal and the voter authority.
+#[allow(clippy::too_many_arguments)]
+pub fn write_voter_weight_record(
+    registrar: &Registrar,
+    voter_key: &Pubkey,
+    voter: &mut Voter,
+    record: &mut VoterWeightRecord,
+    weight_action: Option<VoterWeightAction>,
+    weight_action_target: Option<Pubkey>,
+    vote_accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    let curr_slot = Clock::get()?.slot;
+
+    assert_voter_weight_action(&weight_action, weight_action_target)?;
+    let snapshot_weight = match (&weight_action, weight_action_target) {
+        (Some(VoterWeightAction::CastVote), Some(proposal)) => Some(load_vote_snapshot_weight(
+            registrar,
+            voter_key,
+            voter,
+            proposal,
+            vote_accounts,
+            program_id,
+        )?),
+        _ => None,
+    };
+
+    let min_lockup_secs = registrar.min_lockup_secs_for_action(weight_action.clone());
+    let voter_weight = voter.own_record_weight(registrar, min_lockup_secs)?;
+    record.voter_weight = snapshot_weight.map_or(voter_weight, |w| w.min(voter_weight));
+    record.voter_weight_expiry = registrar.voter_weight_record_expiry(curr_slot);
+    record.account_discriminator = registrar.voter_weight_record_version.discriminator();
+    record.weight_action = weight_action.clone();
+    record.weight_action_target = weight_action_target;
+
+    if let (Some(VoterWeightAction::CastVote), Some(proposal)) =
+        (weight_action, weight_action_target)
+    {
+        if record.voter_weight > 0 {
+            let voter_authority = voter.voter_authority;
+            voter.add_vote_lock(proposal, voter_authority, curr_slot)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrites the voter's own weight into `record`, for the action and target the
+/// record was written for.
+///
+/// Instructions that may reduce the voter's weight call this, so that a record
+/// that outlives the current slot can't be used with the old weight.
+///
+/// Records for CastVote only ever get a lower weight and keep their expiry:
+/// their weight came from the voter's VoterWeightSnapshot. Records without an
+/// action, like the one written by create_voter, get zero weight, since
+/// spl-governance would accept them for votes.
+pub fn reset_voter_weight_record(
+    registrar: &Registrar,
+    voter: &Voter,
+    record: &mut VoterWeightRecord,
+) -> Result<()> {
+    let weight_action = record.weight_action.clone();
+    match weight_action {
+        None => record.voter_weight = 0,
+        Some(VoterWeightAction::CastVote) => {
+            let min_lockup_secs = registrar.min_lockup_secs_for_action(weight_action);
+            let voter_weight = voter.own_record_weight(registrar, min_lockup_secs)?;
+            record.voter_weight = record.voter_weight.min(voter_weight);
+        }
+        Some(_) => {
+            let min_lockup_secs = registrar.min_lockup_secs_for_action(weight_action);
+            record.voter_weight = voter.own_record_weight(registrar, min_lockup_secs)?;
+            record.voter_weight_expiry = registrar.voter_weight_record_expiry(Clock::get()?.slot);
+        }
+    }
+    record.account_discriminator = registrar.voter_weight_record_version.discriminator();
+    Ok(())
+}
+
+/// Like reset_voter_weight_record, for instructions that get the voter's
+/// VoterWeightRecord in `accounts`.
+///
+/// Does nothing with `VoterWeightExpiry::CurrentSlot`, where the record can't be
+/// used after the current slot anyway. Otherwise the record must be in
+/// `accounts` and writable.
+pub fn reset_voter_weight_record_in_accounts(
+    registrar: &Registrar,
+    voter: &Voter,
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    if registrar.voter_weight_expiry == VoterWeightExpiry::CurrentSlot {
+        return Ok(());
+    }
+
+    let address = Pubkey::create_program_address(
+        &[
+            voter.registrar.as_ref(),
+            b"voter-weight-record".as_ref(),
+            voter.voter_authority.as_ref(),
+            &[voter.voter_weight_record_bump],
+        ],
+        program_id,
+    )
+    .unwrap();
+    let info = accounts
+        .iter()
+        .find(|a| a.key() == address)
+        .ok_or_else(|| error!(VsrError::VoterWeightRecordNotFound))?;
+    let mut record = Account::<VoterWeightRecord>::try_from(info)?;
+    reset_voter_weight_record(registrar, voter, &mut record)?;
+    record.exit(program_id)
+}
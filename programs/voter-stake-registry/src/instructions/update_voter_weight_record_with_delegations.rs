@@ -0,0 +1,140 @@
+use crate::error::*;
+use crate::instructions::{
+    assert_voter_weight_action, load_vote_snapshot_weight, UpdateVoterWeightRecord,
+};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+
+/// Like update_voter_weight_record, but also adds the weight of the voters that
+/// delegate to `voter`.
+///
+/// For CastVote, the first four accounts in ctx.remaining_accounts must be the
+/// voter's VoterWeightSnapshot for the proposal, the proposal's governance, the
+/// proposal and the voter authority, signing, like for
+/// update_voter_weight_record. The voter's own weight is capped by the snapshot.
+/// The Voter accounts of the delegators follow, each at most once and at most
+/// MAX_DELEGATORS of them. A delegator either delegates its whole weight with
+/// set_delegate, or single deposit entries with set_deposit_entry_delegate, or
+/// both. Their weight is computed with the same rule for `weight_action`, see
+/// configure_voter_weight_action; the delegators' snapshots are not used.
+///
+/// Delegators are only accepted for CastVote with a target proposal, so the
+/// delegated weight can't be used without being locked: the voter's and each
+/// delegator's weight is locked for the proposal, see release_vote_locks.
+/// Delegator accounts must be writable. Other actions, like CreateProposal,
+/// only count the voter's own weight.
+///
+/// Each delegator adds an account to the transaction and the compute cost of
+/// going over its deposit entries, which is what MAX_DELEGATORS is chosen for.
+/// Delegates with more delegators need the delegations to be consolidated.
+///
+/// With delegators the record always expires at the end of the current slot,
+/// regardless of configure_voter_weight_expiry.
+///
+/// If `voter` itself delegates, only the delegators' weight is written.
+pub fn update_voter_weight_record_with_delegations<'key, 'accounts, 'remaining, 'info>(
+    ctx: Context<'key, 'accounts, 'remaining, 'info, UpdateVoterWeightRecord<'info>>,
+    weight_action: Option<VoterWeightAction>,
+    weight_action_target: Option<Pubkey>,
+) -> Result<()> {
+    let registrar_key = ctx.accounts.registrar.key();
+    let voter_key = ctx.accounts.voter.key();
+    let registrar = &ctx.accounts.registrar.load()?;
+
+    assert_voter_weight_action(&weight_action, weight_action_target)?;
+    let (vote_proposal, snapshot_weight, delegator_infos) =
+        match (&weight_action, weight_action_target) {
+            (Some(VoterWeightAction::CastVote), Some(proposal)) => {
+                let snapshot_weight = load_vote_snapshot_weight(
+                    registrar,
+                    &voter_key,
+                    &*ctx.accounts.voter.load()?,
+                    proposal,
+                    ctx.remaining_accounts,
+                    ctx.program_id,
+                )?;
+                (
+                    Some(proposal),
+                    Some(snapshot_weight),
+                    &ctx.remaining_accounts[4..],
+                )
+            }
+            _ => {
+                require!(
+                    ctx.remaining_accounts.is_empty(),
+                    VsrError::DelegationsRequireVote
+                );
+                (None, None, ctx.remaining_accounts)
+            }
+        };
+    require_gte!(
+        MAX_DELEGATORS,
+        delegator_infos.len(),
+        VsrError::TooManyDelegators
+    );
+    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let voter_authority = voter.voter_authority;
+    let min_lockup_secs = registrar.min_lockup_secs_for_action(weight_action.clone());
+    let curr_slot = Clock::get()?.slot;
+
+    let own_weight = voter.own_record_weight(registrar, min_lockup_secs)?;
+    let mut weight = snapshot_weight.map_or(own_weight, |w| w.min(own_weight));
+    if let Some(proposal) = vote_proposal {
+        if weight > 0 {
+            voter.add_vote_lock(proposal, voter_authority, curr_slot)?;
+        }
+    }
+    for (i, delegator_info) in delegator_infos.iter().enumerate() {
+        require!(
+            delegator_infos[..i]
+                .iter()
+                .all(|a| a.key() != delegator_info.key()),
+            VsrError::InvalidDelegator
+        );
+        let delegator_loader = AccountLoader::<Voter>::try_from(delegator_info)?;
+        let mut delegator = delegator_loader.load_mut()?;
+        require_keys_eq!(
+            delegator.registrar,
+            registrar_key,
+            VsrError::InvalidDelegator
+        );
+
+        let mut delegated_weight =
+            delegator.delegated_weight_with_min_lockup(&voter_key, registrar, min_lockup_secs)?;
+        let is_entry_delegator = delegator
+            .deposits
+            .iter()
+            .any(|d| d.is_used && d.delegate == voter_key);
+        if delegator.delegate == voter_authority {
+            delegated_weight = delegated_weight
+                .checked_add(delegator.weight_with_min_lockup(registrar, min_lockup_secs)?)
+                .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
+        } else {
+            require!(is_entry_delegator, VsrError::InvalidDelegator);
+        }
+        if let Some(proposal) = vote_proposal {
+            if delegated_weight > 0 {
+                delegator.add_vote_lock(proposal, voter_authority, curr_slot)?;
+            }
+        }
+        weight = weight
+            .checked_add(delegated_weight)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
+    }
+
+    let record = &mut ctx.accounts.voter_weight_record;
+    record.voter_weight = weight;
+    // Delegators' weight can change without the delegate's record being
+    // rewritten, so it's only valid in the current slot.
+    record.voter_weight_expiry = if delegator_infos.is_empty() {
+        registrar.voter_weight_record_expiry(curr_slot)
+    } else {
+        Some(curr_slot)
+    };
+    record.account_discriminator = registrar.voter_weight_record_version.discriminator();
+    record.weight_action = weight_action;
+    record.weight_action_target = weight_action_target;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use crate::error::*;
+use crate::events::*;
+use crate::instructions::reset_voter_weight_record;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+// Remaining accounts must be pairs of a Voter account followed by its
+// VoterWeightRecord, which must be writable.
+#[derive(Accounts)]
+pub struct UpdateVoterWeightRecords<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+}
+
+/// Refreshes the VoterWeightRecords of many voters, so they follow the
+/// decay of vote weight between updates. Anyone can call this.
+///
+/// Each record is rewritten like by withdraw, for the action it was written
+/// for, see reset_voter_weight_record. Records for CastVote are never raised.
+///
+/// Pairs whose accounts don't belong together are skipped instead of failing
+/// the batch, for example when the voter was closed, belongs to a different
+/// registrar or the record isn't the voter's or isn't writable.
+///
+/// Emits a VoterWeightChanged event for each record whose weight changed.
+pub fn update_voter_weight_records<'key, 'accounts, 'remaining, 'info>(
+    ctx: Context<'key, 'accounts, 'remaining, 'info, UpdateVoterWeightRecords<'info>>,
+) -> Result<()> {
+    require_eq!(
+        ctx.remaining_accounts.len() % 2,
+        0,
+        VsrError::InvalidVoterWeightRecordPairs
+    );
+    let registrar_key = ctx.accounts.registrar.key();
+    let registrar = &ctx.accounts.registrar.load()?;
+
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let (voter_info, record_info) = (&pair[0], &pair[1]);
+        if !refresh_voter_weight_record(
+            &registrar_key,
+            registrar,
+            voter_info,
+            record_info,
+            ctx.program_id,
+        )? {
+            msg!(
+                "Skipped voter {} with voter weight record {}",
+                voter_info.key(),
+                record_info.key()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrites the record of the voter, returns false if the accounts don't
+/// belong together.
+fn refresh_voter_weight_record(
+    registrar_key: &Pubkey,
+    registrar: &Registrar,
+    voter_info: &AccountInfo,
+    record_info: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<bool> {
+    let voter_loader = match AccountLoader::<Voter>::try_from(voter_info) {
+        Ok(loader) => loader,
+        Err(_) => return Ok(false),
+    };
+    let voter = voter_loader.load()?;
+    if voter.registrar != *registrar_key {
+        return Ok(false);
+    }
+
+    let address = Pubkey::create_program_address(
+        &[
+            registrar_key.as_ref(),
+            b"voter-weight-record".as_ref(),
+            voter.voter_authority.as_ref(),
+            &[voter.voter_weight_record_bump],
+        ],
+        program_id,
+    );
+    if address.ok() != Some(record_info.key()) || !record_info.is_writable {
+        return Ok(false);
+    }
+    let mut record = match Account::<VoterWeightRecord>::try_from(record_info) {
+        Ok(record) => record,
+        Err(_) => return Ok(false),
+    };
+
+    let previous_voter_weight = record.voter_weight;
+    reset_voter_weight_record(registrar, &voter, &mut record)?;
+    record.exit(program_id)?;
+
+    if record.voter_weight != previous_voter_weight {
+        emit!(VoterWeightChanged {
+            voter: voter_info.key(),
+            voter_authority: voter.voter_authority,
+            previous_voter_weight,
+            voter_weight: record.voter_weight,
+        });
+    }
+    Ok(true)
+}
@@ -1,10 +1,12 @@
 use crate::error::*;
+use crate::instructions::reset_voter_weight_record;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -72,6 +74,13 @@ impl<'info> Withdraw<'info> {
 ///
 /// `deposit_entry_index`: The deposit entry to withdraw from.
 /// `amount` is in units of the native currency being withdrawn.
+///
+/// If the voter delegates its vote weight, or the deposit entry is delegated,
+/// the delegate's token owner record must be passed in ctx.remaining_accounts,
+/// for a delegated deposit entry together with the delegate's Voter account.
+///
+/// Withdraws are also blocked while the voter's weight is locked for a vote,
+/// see release_vote_locks.
 pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) -> Result<()> {
     {
         // Transfer the tokens to withdraw.
@@ -84,8 +93,9 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
     }
 
     // Load the accounts.
-    let registrar = &ctx.accounts.registrar.load()?;
+    let registrar = ctx.accounts.registrar.load()?;
     let voter = &mut ctx.accounts.voter.load_mut()?;
+    let mut deposit_totals = DepositTotalsChange::new(&registrar, voter);
 
     // Get the exchange rate for the token being withdrawn.
     let mint_idx = registrar.voting_mint_config_index(ctx.accounts.destination.mint)?;
@@ -95,14 +105,25 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
     if registrar.voting_mints[mint_idx].grants_vote_weight() {
         let token_owner_record = voter.load_token_owner_record(
             &ctx.accounts.token_owner_record.to_account_info(),
-            registrar,
+            &registrar,
         )?;
         token_owner_record.assert_can_withdraw_governing_tokens()?;
+        voter.assert_delegate_can_withdraw(ctx.remaining_accounts, &registrar)?;
+        voter.assert_entry_delegate_can_withdraw(
+            deposit_entry_index,
+            ctx.remaining_accounts,
+            &registrar,
+        )?;
+        voter.assert_no_active_votes()?;
     }
 
     // Get the deposit being withdrawn from.
     let curr_ts = registrar.clock_unix_timestamp();
     let deposit_entry = voter.active_deposit_mut(deposit_entry_index)?;
+    require!(
+        !deposit_entry.has_lock_delegate(),
+        VsrError::DepositEntryHasLockDelegate
+    );
     require_gte!(
         deposit_entry.amount_unlocked(curr_ts),
         amount,
@@ -120,10 +141,12 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
         amount,
         VsrError::InternalProgramError
     );
+    let deposit_entry_before = *deposit_entry;
     deposit_entry.amount_deposited_native = deposit_entry
         .amount_deposited_native
         .checked_sub(amount)
         .unwrap();
+    deposit_totals.record(&deposit_entry_before, deposit_entry);
 
     msg!(
         "Withdrew amount {} at deposit index {} with lockup kind {:?} and {} seconds left",
@@ -133,10 +156,17 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
         deposit_entry.lockup.seconds_left(curr_ts),
     );
 
-    // Update the voter weight record
-    let record = &mut ctx.accounts.voter_weight_record;
-    record.voter_weight = voter.weight(registrar)?;
-    record.voter_weight_expiry = Some(Clock::get()?.slot);
+    // Update the voter weight record, valid for any action
+    reset_voter_weight_record(&registrar, voter, &mut ctx.accounts.voter_weight_record)?;
+
+    record_voter_weight_checkpoint(
+        &ctx.accounts.voter.key(),
+        voter,
+        &registrar,
+        ctx.remaining_accounts,
+        ctx.program_id,
+    )?;
 
-    Ok(())
+    drop(registrar);
+    deposit_totals.apply(&ctx.accounts.registrar)
 }
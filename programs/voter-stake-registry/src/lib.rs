@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 use instructions::*;
+pub use instructions::{GrantBatchItem, VoterWeights};
+use spl_governance::state::{proposal::VoteType, vote_record::Vote};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
 use state::*;
 
 mod error;
@@ -41,11 +44,14 @@ declare_id!("vsr2nfGVNHmSY8uxoBGqq8AQbwz3JwaEaHqGbsTPXqQ");
 ///
 /// # Interacting with SPL Governance
 ///
-/// This program does not directly interact with SPL governance via CPI.
+/// This program mostly does not interact with SPL governance via CPI.
 /// Instead, it simply writes a `VoterWeightRecord` account with a well defined
 /// format, which is then used by SPL governance as the voting power measurement
 /// for a given user.
 ///
+/// For convenience, `cast_vote`, `create_proposal` and `relinquish_vote` refresh
+/// the record and call into SPL governance in a single instruction.
+///
 /// # Max Vote Weight
 ///
 /// Given that one can use multiple tokens to vote, the max vote weight needs
@@ -57,7 +63,11 @@ declare_id!("vsr2nfGVNHmSY8uxoBGqq8AQbwz3JwaEaHqGbsTPXqQ");
 /// exchange rate, which must be fixed.
 ///
 /// Note that the above also implies that the `max_vote_weight` must fit into
-/// a u64.
+/// a u64. Vote weights are computed as u128 internally, and the registrar can
+/// scale them down before output, see `configure_vote_weight_output`.
+///
+/// Alternatively, the registrar can compute the max vote weight from the
+/// deposited tokens instead of the supply, see `configure_max_vote_weight_source`.
 #[program]
 pub mod voter_stake_registry {
     use super::*;
@@ -86,6 +96,113 @@ pub mod voter_stake_registry {
         )
     }
 
+    pub fn configure_voting_mint_schedule(
+        ctx: Context<ConfigureVotingMintSchedule>,
+        idx: u16,
+        baseline_vote_weight_scaled_factor_end: u64,
+        max_extra_lockup_vote_weight_scaled_factor_end: u64,
+        schedule_start_ts: i64,
+        schedule_end_ts: i64,
+    ) -> Result<()> {
+        instructions::configure_voting_mint_schedule(
+            ctx,
+            idx,
+            baseline_vote_weight_scaled_factor_end,
+            max_extra_lockup_vote_weight_scaled_factor_end,
+            schedule_start_ts,
+            schedule_end_ts,
+        )
+    }
+
+    pub fn configure_clawback_authority(
+        ctx: Context<ConfigureClawbackAuthority>,
+        idx: u16,
+        clawback_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::configure_clawback_authority(ctx, idx, clawback_authority)
+    }
+
+    pub fn configure_clawback_destination(
+        ctx: Context<ConfigureClawbackDestination>,
+        idx: u16,
+        clawback_destination: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::configure_clawback_destination(ctx, idx, clawback_destination)
+    }
+
+    pub fn configure_clawback_policy(
+        ctx: Context<ConfigureClawbackPolicy>,
+        policy: ClawbackPolicy,
+    ) -> Result<()> {
+        instructions::configure_clawback_policy(ctx, policy)
+    }
+
+    pub fn configure_voter_weight_record_version(
+        ctx: Context<ConfigureVoterWeightRecordVersion>,
+        version: VoterWeightRecordVersion,
+    ) -> Result<()> {
+        instructions::configure_voter_weight_record_version(ctx, version)
+    }
+
+    pub fn configure_voter_weight_expiry(
+        ctx: Context<ConfigureVoterWeightExpiry>,
+        expiry: VoterWeightExpiry,
+        slots: u64,
+    ) -> Result<()> {
+        instructions::configure_voter_weight_expiry(ctx, expiry, slots)
+    }
+
+    pub fn configure_lockup_warmup(
+        ctx: Context<ConfigureLockupWarmup>,
+        idx: u16,
+        pre_start_lockup_bonus_scaled_factor: Option<u64>,
+    ) -> Result<()> {
+        instructions::configure_lockup_warmup(ctx, idx, pre_start_lockup_bonus_scaled_factor)
+    }
+
+    pub fn configure_supply_exclusions(
+        ctx: Context<ConfigureSupplyExclusions>,
+        token_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::configure_supply_exclusions(ctx, token_accounts)
+    }
+
+    pub fn configure_max_vote_weight_source(
+        ctx: Context<ConfigureMaxVoteWeightSource>,
+        source: MaxVoteWeightSource,
+    ) -> Result<()> {
+        instructions::configure_max_vote_weight_source(ctx, source)
+    }
+
+    pub fn configure_vote_weight_output(
+        ctx: Context<ConfigureVoteWeightOutput>,
+        vote_weight_output_digit_shift: i8,
+    ) -> Result<()> {
+        instructions::configure_vote_weight_output(ctx, vote_weight_output_digit_shift)
+    }
+
+    pub fn configure_voter_weight_action(
+        ctx: Context<ConfigureVoterWeightAction>,
+        action: VoterWeightAction,
+        min_lockup_secs: u64,
+    ) -> Result<()> {
+        instructions::configure_voter_weight_action(ctx, action, min_lockup_secs)
+    }
+
+    pub fn configure_unlock_early(
+        ctx: Context<ConfigureUnlockEarly>,
+        idx: u16,
+        penalty_destination: Option<Pubkey>,
+        max_penalty_scaled_factor: u64,
+    ) -> Result<()> {
+        instructions::configure_unlock_early(
+            ctx,
+            idx,
+            penalty_destination,
+            max_penalty_scaled_factor,
+        )
+    }
+
     pub fn create_voter(
         ctx: Context<CreateVoter>,
         voter_bump: u8,
@@ -120,6 +237,14 @@ pub mod voter_stake_registry {
         instructions::withdraw(ctx, deposit_entry_index, amount)
     }
 
+    pub fn unlock_early(
+        ctx: Context<UnlockEarly>,
+        deposit_entry_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::unlock_early(ctx, deposit_entry_index, amount)
+    }
+
     pub fn grant(
         ctx: Context<Grant>,
         voter_bump: u8,
@@ -129,6 +254,7 @@ pub mod voter_stake_registry {
         periods: u32,
         allow_clawback: bool,
         amount: u64,
+        fund_over_time: bool,
     ) -> Result<()> {
         instructions::grant(
             ctx,
@@ -139,11 +265,35 @@ pub mod voter_stake_registry {
             periods,
             allow_clawback,
             amount,
+            fund_over_time,
         )
     }
 
-    pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
-        instructions::clawback(ctx, deposit_entry_index)
+    pub fn fund_grant(ctx: Context<FundGrant>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+        instructions::fund_grant(ctx, deposit_entry_index, amount)
+    }
+
+    pub fn grant_batch<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, GrantBatch<'info>>,
+        kind: LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+        grants: Vec<GrantBatchItem>,
+    ) -> Result<()> {
+        instructions::grant_batch(ctx, kind, start_ts, periods, allow_clawback, grants)
+    }
+
+    pub fn clawback(
+        ctx: Context<Clawback>,
+        deposit_entry_index: u8,
+        amount: Option<u64>,
+    ) -> Result<()> {
+        instructions::clawback(ctx, deposit_entry_index, amount)
+    }
+
+    pub fn revoke_grant(ctx: Context<RevokeGrant>, deposit_entry_index: u8) -> Result<()> {
+        instructions::revoke_grant(ctx, deposit_entry_index)
     }
 
     pub fn close_deposit_entry(
@@ -153,6 +303,21 @@ pub mod voter_stake_registry {
         instructions::close_deposit_entry(ctx, deposit_entry_index)
     }
 
+    pub fn set_lock_delegate(
+        ctx: Context<SetLockDelegate>,
+        deposit_entry_index: u8,
+        lock_delegate: Pubkey,
+    ) -> Result<()> {
+        instructions::set_lock_delegate(ctx, deposit_entry_index, lock_delegate)
+    }
+
+    pub fn release_lock_delegate(
+        ctx: Context<ReleaseLockDelegate>,
+        deposit_entry_index: u8,
+    ) -> Result<()> {
+        instructions::release_lock_delegate(ctx, deposit_entry_index)
+    }
+
     pub fn reset_lockup(
         ctx: Context<ResetLockup>,
         deposit_entry_index: u8,
@@ -190,14 +355,102 @@ pub mod voter_stake_registry {
         )
     }
 
-    pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
-        instructions::update_voter_weight_record(ctx)
+    pub fn create_voter_weight_checkpoints(
+        ctx: Context<CreateVoterWeightCheckpoints>,
+    ) -> Result<()> {
+        instructions::create_voter_weight_checkpoints(ctx)
+    }
+
+    pub fn create_voter_weight_snapshot(ctx: Context<CreateVoterWeightSnapshot>) -> Result<()> {
+        instructions::create_voter_weight_snapshot(ctx)
+    }
+
+    pub fn update_voter_weight_record(
+        ctx: Context<UpdateVoterWeightRecord>,
+        weight_action: Option<VoterWeightAction>,
+        weight_action_target: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_voter_weight_record(ctx, weight_action, weight_action_target)
+    }
+
+    pub fn update_voter_weight_record_with_delegations<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, UpdateVoterWeightRecord<'info>>,
+        weight_action: Option<VoterWeightAction>,
+        weight_action_target: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_voter_weight_record_with_delegations(
+            ctx,
+            weight_action,
+            weight_action_target,
+        )
+    }
+
+    pub fn update_voter_weight_records<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, UpdateVoterWeightRecords<'info>>,
+    ) -> Result<()> {
+        instructions::update_voter_weight_records(ctx)
+    }
+
+    pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+        instructions::set_delegate(ctx, delegate)
+    }
+
+    pub fn set_deposit_entry_delegate(
+        ctx: Context<SetDepositEntryDelegate>,
+        deposit_entry_index: u8,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        instructions::set_deposit_entry_delegate(ctx, deposit_entry_index, delegate)
+    }
+
+    pub fn release_vote_locks(ctx: Context<ReleaseVoteLocks>) -> Result<()> {
+        instructions::release_vote_locks(ctx)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, vote: Vote) -> Result<()> {
+        instructions::cast_vote(ctx, vote)
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        name: String,
+        description_link: String,
+        vote_type: VoteType,
+        options: Vec<String>,
+        use_deny_option: bool,
+        proposal_index: u32,
+    ) -> Result<()> {
+        instructions::create_proposal(
+            ctx,
+            name,
+            description_link,
+            vote_type,
+            options,
+            use_deny_option,
+            proposal_index,
+        )
+    }
+
+    pub fn relinquish_vote(ctx: Context<RelinquishVote>) -> Result<()> {
+        instructions::relinquish_vote(ctx)
     }
 
     pub fn update_max_vote_weight(ctx: Context<UpdateMaxVoteWeight>) -> Result<()> {
         instructions::update_max_vote_weight(ctx)
     }
 
+    pub fn sync_deposit_totals(ctx: Context<SyncDepositTotals>) -> Result<()> {
+        instructions::sync_deposit_totals(ctx)
+    }
+
+    pub fn migrate_registrar(ctx: Context<MigrateRegistrar>) -> Result<()> {
+        instructions::migrate_registrar(ctx)
+    }
+
+    pub fn migrate_voter(ctx: Context<MigrateVoter>) -> Result<()> {
+        instructions::migrate_voter(ctx)
+    }
+
     pub fn close_voter<'key, 'accounts, 'remaining, 'info>(
         ctx: Context<'key, 'accounts, 'remaining, 'info, CloseVoter<'info>>,
     ) -> Result<()> {
@@ -212,6 +465,14 @@ pub mod voter_stake_registry {
         instructions::log_voter_info(ctx, deposit_entry_begin, deposit_entry_count)
     }
 
+    pub fn get_voter_weight_at(ctx: Context<GetVoterWeightAt>, ts: i64) -> Result<()> {
+        instructions::get_voter_weight_at(ctx, ts)
+    }
+
+    pub fn get_voter_weights(ctx: Context<GetVoterWeights>, at_ts: i64) -> Result<()> {
+        instructions::get_voter_weights(ctx, at_ts)
+    }
+
     pub fn set_time_offset(ctx: Context<SetTimeOffset>, time_offset: i64) -> Result<()> {
         instructions::set_time_offset(ctx, time_offset)
     }
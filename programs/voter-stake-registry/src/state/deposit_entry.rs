@@ -29,6 +29,15 @@ pub struct DepositEntry {
     /// which should not change due to withdraws.
     pub amount_initially_locked_native: u64,
 
+    /// For grants that are funded over time: the total amount of tokens the
+    /// grantor committed to. These tokens vest on the schedule of the lockup.
+    /// Zero for all other deposit entries.
+    pub grant_promised_native: u64,
+
+    /// For grants that are funded over time: the amount of tokens added with
+    /// fund_grant so far. Never exceeds the vested part of grant_promised_native.
+    pub grant_funded_native: u64,
+
     // True if the deposit entry is being used.
     pub is_used: bool,
 
@@ -38,12 +47,69 @@ pub struct DepositEntry {
     // Points to the VotingMintConfig this deposit uses.
     pub voting_mint_config_idx: u8,
 
-    pub reserved: [u8; 29],
+    /// The authority that created this deposit entry with the grant instruction,
+    /// or Pubkey::default() for entries created with create_deposit_entry.
+    pub grantor: Pubkey,
+
+    /// An authority, usually a PDA of another program, that keeps the tokens of
+    /// this deposit entry in place, or Pubkey::default() if there is none.
+    ///
+    /// While set, tokens can't be withdrawn or moved out of the entry and the
+    /// lockup can't be changed. Only the lock delegate can release the lock.
+    pub lock_delegate: Pubkey,
+
+    /// The Voter account that the vote weight of this deposit entry is
+    /// delegated to, or Pubkey::default() if it counts for the owning voter.
+    pub delegate: Pubkey,
+
+    pub reserved: [u8; 13],
 }
-const_assert!(std::mem::size_of::<DepositEntry>() == 32 + 2 * 8 + 3 + 29);
+const_assert!(std::mem::size_of::<DepositEntry>() == 32 + 4 * 8 + 3 + 3 * 32 + 13);
 const_assert!(std::mem::size_of::<DepositEntry>() % 8 == 0);
 
 impl DepositEntry {
+    /// Whether this deposit entry was created by the grant instruction.
+    pub fn is_grant(&self) -> bool {
+        self.grantor != Pubkey::default()
+    }
+
+    /// Whether this deposit entry is locked in place by a lock delegate.
+    pub fn has_lock_delegate(&self) -> bool {
+        self.lock_delegate != Pubkey::default()
+    }
+
+    /// Whether the vote weight of this deposit entry is delegated to another voter.
+    pub fn is_delegated(&self) -> bool {
+        self.delegate != Pubkey::default()
+    }
+
+    /// Whether this deposit entry is a grant whose tokens are added over time
+    /// with fund_grant.
+    ///
+    /// The lockup of such entries only describes the vesting schedule of the
+    /// promised tokens. All funded tokens are unlocked and the lockup must not
+    /// be changed.
+    pub fn is_funded_over_time(&self) -> bool {
+        self.grant_promised_native > 0
+    }
+
+    /// Whether this is a grant whose lockup has not started at curr_ts.
+    ///
+    /// The grantor can still revoke such grants and take back all their tokens,
+    /// so the voter must not add tokens to them or move tokens out of them.
+    pub fn is_revocable_grant(&self, curr_ts: i64) -> bool {
+        self.is_grant() && curr_ts < self.lockup.start_ts
+    }
+
+    /// The amount of promised grant tokens that have vested at curr_ts.
+    pub fn grant_vested(&self, curr_ts: i64) -> Result<u64> {
+        let schedule = DepositEntry {
+            amount_initially_locked_native: self.grant_promised_native,
+            ..*self
+        };
+        schedule.vested(curr_ts)
+    }
+
     /// # Voting Power Caclulation
     ///
     /// Returns the voting power for the deposit, giving locked tokens boosted
@@ -83,15 +149,21 @@ impl DepositEntry {
     /// Daily/monthly linear vesting can be calculated with series sum, see
     /// voting_power_linear_vesting() below.
     ///
-    pub fn voting_power(&self, voting_mint_config: &VotingMintConfig, curr_ts: i64) -> Result<u64> {
+    /// The result is an internal u128 vote weight, see Registrar::vote_weight_output().
+    pub fn voting_power(
+        &self,
+        voting_mint_config: &VotingMintConfig,
+        curr_ts: i64,
+    ) -> Result<u128> {
         let baseline_vote_weight =
-            voting_mint_config.baseline_vote_weight(self.amount_deposited_native)?;
-        let max_locked_vote_weight =
-            voting_mint_config.max_extra_lockup_vote_weight(self.amount_initially_locked_native)?;
+            voting_mint_config.baseline_vote_weight(self.amount_deposited_native, curr_ts)?;
+        let max_locked_vote_weight = voting_mint_config
+            .max_extra_lockup_vote_weight(self.amount_initially_locked_native, curr_ts)?;
         let locked_vote_weight = self.voting_power_locked(
             curr_ts,
             max_locked_vote_weight,
             voting_mint_config.lockup_saturation_secs,
+            voting_mint_config.lockup_warmup_factor(),
         )?;
         require_gte!(
             max_locked_vote_weight,
@@ -104,12 +176,45 @@ impl DepositEntry {
     }
 
     /// Vote power contribution from locked funds only.
+    ///
+    /// If `lockup_warmup_factor` is set, the result is scaled by it while
+    /// `curr_ts` is before the lockup's start_ts.
     pub fn voting_power_locked(
         &self,
         curr_ts: i64,
-        max_locked_vote_weight: u64,
+        max_locked_vote_weight: u128,
+        lockup_saturation_secs: u64,
+        lockup_warmup_factor: Option<u64>,
+    ) -> Result<u128> {
+        let vote_weight = self.voting_power_locked_without_warmup(
+            curr_ts,
+            max_locked_vote_weight,
+            lockup_saturation_secs,
+        )?;
+        self.apply_lockup_warmup(vote_weight, curr_ts, lockup_warmup_factor)
+    }
+
+    /// Scales down lockup vote weight at `curr_ts` if the lockup has not started yet.
+    fn apply_lockup_warmup(
+        &self,
+        locked_vote_weight: u128,
+        curr_ts: i64,
+        lockup_warmup_factor: Option<u64>,
+    ) -> Result<u128> {
+        match lockup_warmup_factor {
+            Some(factor) if curr_ts < self.lockup.start_ts => {
+                VotingMintConfig::apply_factor(locked_vote_weight, factor)
+            }
+            _ => Ok(locked_vote_weight),
+        }
+    }
+
+    fn voting_power_locked_without_warmup(
+        &self,
+        curr_ts: i64,
+        max_locked_vote_weight: u128,
         lockup_saturation_secs: u64,
-    ) -> Result<u64> {
+    ) -> Result<u128> {
         if self.lockup.expired(curr_ts) || max_locked_vote_weight == 0 {
             return Ok(0);
         }
@@ -138,13 +243,17 @@ impl DepositEntry {
     /// they can to unlock as quickly as possible at `curr_ts`.
     ///
     /// Currently that means that Constant lockups get turned into Cliff lockups.
+    ///
+    /// The lockup warmup is judged by the original start_ts: an unstarted Constant
+    /// lockup only gets `lockup_warmup_factor` of the Cliff lockup's vote weight.
     pub fn voting_power_locked_guaranteed(
         &self,
         curr_ts: i64,
         at_ts: i64,
-        max_locked_vote_weight: u64,
+        max_locked_vote_weight: u128,
         lockup_saturation_secs: u64,
-    ) -> Result<u64> {
+        lockup_warmup_factor: Option<u64>,
+    ) -> Result<u128> {
         let mut altered = self.clone();
 
         // Trigger the unlock phase for constant lockups
@@ -159,34 +268,36 @@ impl DepositEntry {
         // Other lockup types don't need changes, because the user
         // cannot reduce their lockup strength.
 
-        altered.voting_power_locked(at_ts, max_locked_vote_weight, lockup_saturation_secs)
+        let vote_weight = altered.voting_power_locked_without_warmup(
+            at_ts,
+            max_locked_vote_weight,
+            lockup_saturation_secs,
+        )?;
+        self.apply_lockup_warmup(vote_weight, at_ts, lockup_warmup_factor)
     }
 
     /// Vote power contribution from funds with linear vesting.
     fn voting_power_cliff(
         &self,
         curr_ts: i64,
-        max_locked_vote_weight: u64,
+        max_locked_vote_weight: u128,
         lockup_saturation_secs: u64,
-    ) -> Result<u64> {
+    ) -> Result<u128> {
         let remaining = min(self.lockup.seconds_left(curr_ts), lockup_saturation_secs);
-        Ok(u64::try_from(
-            (max_locked_vote_weight as u128)
-                .checked_mul(remaining as u128)
-                .unwrap()
-                .checked_div(lockup_saturation_secs as u128)
-                .unwrap(),
-        )
-        .unwrap())
+        Ok(max_locked_vote_weight
+            .checked_mul(remaining as u128)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?
+            .checked_div(lockup_saturation_secs as u128)
+            .unwrap())
     }
 
     /// Vote power contribution from cliff-locked funds.
     fn voting_power_linear_vesting(
         &self,
         curr_ts: i64,
-        max_locked_vote_weight: u64,
+        max_locked_vote_weight: u128,
         lockup_saturation_secs: u64,
-    ) -> Result<u64> {
+    ) -> Result<u128> {
         let periods_left = self.lockup.periods_left(curr_ts)?;
         let periods_total = self.lockup.periods_total()?;
         let period_secs = self.lockup.kind.period_secs() as u64;
@@ -280,14 +391,11 @@ impl DepositEntry {
             + lockup_secs_full as u128
             + lockup_secs_saturated as u128;
 
-        Ok(u64::try_from(
-            (max_locked_vote_weight as u128)
-                .checked_mul(lockup_secs)
-                .unwrap()
-                .checked_div(denominator as u128)
-                .unwrap(),
-        )
-        .unwrap())
+        Ok(max_locked_vote_weight
+            .checked_mul(lockup_secs)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?
+            .checked_div(denominator as u128)
+            .unwrap())
     }
 
     /// Returns the amount of unlocked tokens for this deposit--in native units
@@ -340,6 +448,19 @@ impl DepositEntry {
             .unwrap()
     }
 
+    /// Upper bound on the native tokens that may receive extra lockup vote weight.
+    ///
+    /// Expired lockups are only excluded once the deposit entry is changed again.
+    pub fn amount_lockup_weighted_upper_bound(&self) -> u64 {
+        if self.lockup.kind == LockupKind::None {
+            return 0;
+        }
+        min(
+            self.amount_initially_locked_native,
+            self.amount_deposited_native,
+        )
+    }
+
     /// Adjusts the deposit and remaining lockup periods such that
     /// no parts of amount_initially_locked_native have vested.
     ///
@@ -381,7 +502,7 @@ impl DepositEntry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::LockupKind::{Constant, Daily};
+    use crate::LockupKind::{Cliff, Constant, Daily};
 
     #[test]
     pub fn resolve_vesting() -> Result<()> {
@@ -392,7 +513,12 @@ mod tests {
             is_used: true,
             allow_clawback: false,
             voting_mint_config_idx: 0,
-            reserved: [0; 29],
+            grantor: Pubkey::default(),
+            grant_promised_native: 0,
+            grant_funded_native: 0,
+            lock_delegate: Pubkey::default(),
+            delegate: Pubkey::default(),
+            reserved: [0; 13],
         };
         let initial_deposit = deposit.clone();
         let month = deposit.lockup.kind.period_secs() as i64;
@@ -466,7 +592,12 @@ mod tests {
             is_used: true,
             allow_clawback: false,
             voting_mint_config_idx: 0,
-            reserved: [0; 29],
+            grantor: Pubkey::default(),
+            grant_promised_native: 0,
+            grant_funded_native: 0,
+            lock_delegate: Pubkey::default(),
+            delegate: Pubkey::default(),
+            reserved: [0; 13],
         };
         let voting_mint_config = VotingMintConfig {
             mint: Pubkey::default(),
@@ -475,15 +606,14 @@ mod tests {
             max_extra_lockup_vote_weight_scaled_factor: 1_000_000_000, // 1x
             lockup_saturation_secs: saturation as u64,
             digit_shift: 0,
-            reserved1: [0; 7],
-            reserved2: [0; 7],
+            ..VotingMintConfig::default()
         };
 
         let baseline_vote_weight =
-            voting_mint_config.baseline_vote_weight(deposit.amount_deposited_native)?;
+            voting_mint_config.baseline_vote_weight(deposit.amount_deposited_native, 0)?;
         assert_eq!(baseline_vote_weight, 10_000);
         let max_locked_vote_weight = voting_mint_config
-            .max_extra_lockup_vote_weight(deposit.amount_initially_locked_native)?;
+            .max_extra_lockup_vote_weight(deposit.amount_initially_locked_native, 0)?;
         assert_eq!(max_locked_vote_weight, 10_000);
 
         // The timestamp 100_000 is very far before the lockup_start timestamp
@@ -538,7 +668,12 @@ mod tests {
             is_used: true,
             allow_clawback: false,
             voting_mint_config_idx: 0,
-            reserved: [0; 29],
+            grantor: Pubkey::default(),
+            grant_promised_native: 0,
+            grant_funded_native: 0,
+            lock_delegate: Pubkey::default(),
+            delegate: Pubkey::default(),
+            reserved: [0; 13],
         };
 
         let v = |curr_offset, at_offset| {
@@ -548,6 +683,7 @@ mod tests {
                     start + at_offset,
                     100,
                     saturation,
+                    None,
                 )
                 .unwrap()
         };
@@ -568,4 +704,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn lockup_warmup_test() -> Result<()> {
+        // Check that lockups that have not started get reduced lockup vote weight.
+        let day: i64 = 86_400;
+        let saturation = (10 * day) as u64;
+        let start = 10_000_000_000; // arbitrary point
+        let deposit = DepositEntry {
+            amount_deposited_native: 10_000,
+            amount_initially_locked_native: 10_000,
+            lockup: Lockup {
+                start_ts: start,
+                end_ts: start + 5 * day,
+                kind: Cliff,
+                reserved: [0; 15],
+            },
+            is_used: true,
+            allow_clawback: false,
+            voting_mint_config_idx: 0,
+            grantor: Pubkey::default(),
+            grant_promised_native: 0,
+            grant_funded_native: 0,
+            lock_delegate: Pubkey::default(),
+            delegate: Pubkey::default(),
+            reserved: [0; 13],
+        };
+
+        let v = |offset, factor| {
+            deposit
+                .voting_power_locked(start + offset, 100, saturation, factor)
+                .unwrap()
+        };
+        // without warmup, the lockup is treated as lasting from now
+        assert_eq!(v(-day, None), 60);
+        assert_eq!(v(0, None), 50);
+        // baseline only before start
+        assert_eq!(v(-day, Some(0)), 0);
+        assert_eq!(v(-1, Some(0)), 0);
+        assert_eq!(v(0, Some(0)), 50);
+        assert_eq!(v(day, Some(0)), 40);
+        // a fraction of the bonus before start
+        assert_eq!(v(-day, Some(500_000_000)), 30);
+        assert_eq!(v(0, Some(500_000_000)), 50);
+
+        let g = |curr_offset, at_offset, factor| {
+            deposit
+                .voting_power_locked_guaranteed(
+                    start + curr_offset,
+                    start + at_offset,
+                    100,
+                    saturation,
+                    factor,
+                )
+                .unwrap()
+        };
+        assert_eq!(g(-2 * day, -day, None), 60);
+        assert_eq!(g(-2 * day, -day, Some(0)), 0);
+        assert_eq!(g(-2 * day, day, Some(0)), 40);
+
+        Ok(())
+    }
 }
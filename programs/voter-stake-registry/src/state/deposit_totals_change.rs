@@ -0,0 +1,77 @@
+use crate::state::deposit_entry::DepositEntry;
+use crate::state::registrar::Registrar;
+use crate::state::voter::Voter;
+use anchor_lang::prelude::*;
+
+/// Changes to the deposit totals of the registrar's voting mints.
+///
+/// Instructions that change deposit entries collect the changes while the
+/// registrar is only borrowed for reading and apply them at the end. Changes
+/// are only recorded with MaxVoteWeightSource::Deposits and for voters that
+/// are synced to the registrar's deposit_totals_epoch.
+#[derive(Default)]
+pub struct DepositTotalsChange {
+    deposited: [i128; 4],
+    lockup_weighted: [i128; 4],
+    has_changes: bool,
+
+    /// Whether the voter whose deposit entries are recorded is part of the totals.
+    voter_is_tracked: bool,
+}
+
+impl DepositTotalsChange {
+    pub fn new(registrar: &Registrar, voter: &Voter) -> Self {
+        let mut change = Self::default();
+        change.set_voter(registrar, voter);
+        change
+    }
+
+    /// Selects the voter that the following record() calls are for.
+    pub fn set_voter(&mut self, registrar: &Registrar, voter: &Voter) {
+        self.voter_is_tracked = registrar.tracks_deposit_totals_of(voter);
+    }
+
+    /// Records that a deposit entry changed from `before` to `after`.
+    ///
+    /// Does nothing if the registrar doesn't track the voter's deposits.
+    pub fn record(&mut self, before: &DepositEntry, after: &DepositEntry) {
+        if !self.voter_is_tracked {
+            return;
+        }
+        self.add(before, -1);
+        self.add(after, 1);
+    }
+
+    /// Records all deposit entries of a voter that isn't part of the totals yet.
+    pub fn record_all(&mut self, voter: &Voter) {
+        for entry in voter.deposits.iter() {
+            self.add(entry, 1);
+        }
+    }
+
+    fn add(&mut self, entry: &DepositEntry, sign: i128) {
+        if !entry.is_used {
+            return;
+        }
+        let idx = entry.voting_mint_config_idx as usize;
+        self.deposited[idx] += sign * i128::from(entry.amount_deposited_native);
+        self.lockup_weighted[idx] += sign * i128::from(entry.amount_lockup_weighted_upper_bound());
+        self.has_changes = true;
+    }
+
+    pub fn apply_to(&self, registrar: &mut Registrar) -> Result<()> {
+        for (idx, config) in registrar.voting_mints.iter_mut().enumerate() {
+            config.update_deposit_totals(self.deposited[idx], self.lockup_weighted[idx])?;
+        }
+        Ok(())
+    }
+
+    /// Applies the recorded changes to the registrar, which must not be
+    /// borrowed anymore.
+    pub fn apply(&self, registrar: &AccountLoader<Registrar>) -> Result<()> {
+        if !self.has_changes {
+            return Ok(());
+        }
+        self.apply_to(&mut *registrar.load_mut()?)
+    }
+}
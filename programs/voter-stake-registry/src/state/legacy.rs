@@ -0,0 +1,158 @@
+use crate::state::deposit_entry::DepositEntry;
+use crate::state::lockup::Lockup;
+use crate::state::registrar::Registrar;
+use crate::state::voter::Voter;
+use crate::state::voting_mint_config::VotingMintConfig;
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+/// Layout of VotingMintConfig up to v0.2.4.
+///
+/// VotingMintConfig keeps these fields at the same offsets. Its later fields
+/// use the reserved bytes, which are zero in registrars of these versions.
+#[zero_copy]
+pub struct VotingMintConfigV0 {
+    pub mint: Pubkey,
+    pub grant_authority: Pubkey,
+    pub baseline_vote_weight_scaled_factor: u64,
+    pub max_extra_lockup_vote_weight_scaled_factor: u64,
+    pub lockup_saturation_secs: u64,
+    pub digit_shift: i8,
+    pub reserved1: [u8; 7],
+    pub reserved2: [u64; 7],
+}
+const_assert!(std::mem::size_of::<VotingMintConfigV0>() == 2 * 32 + 3 * 8 + 1 + 63);
+const_assert!(std::mem::size_of::<VotingMintConfigV0>() % 8 == 0);
+
+impl VotingMintConfigV0 {
+    /// The config in the current layout. New fields are zero.
+    pub fn migrate(&self) -> VotingMintConfig {
+        VotingMintConfig {
+            mint: self.mint,
+            grant_authority: self.grant_authority,
+            baseline_vote_weight_scaled_factor: self.baseline_vote_weight_scaled_factor,
+            max_extra_lockup_vote_weight_scaled_factor: self
+                .max_extra_lockup_vote_weight_scaled_factor,
+            lockup_saturation_secs: self.lockup_saturation_secs,
+            digit_shift: self.digit_shift,
+            ..VotingMintConfig::default()
+        }
+    }
+}
+
+/// Layout of Registrar up to v0.2.4, without the account discriminator.
+///
+/// Used by migrate_registrar to convert registrars created by these versions.
+#[zero_copy]
+pub struct RegistrarV0 {
+    pub governance_program_id: Pubkey,
+    pub realm: Pubkey,
+    pub realm_governing_token_mint: Pubkey,
+    pub realm_authority: Pubkey,
+    pub reserved1: [u8; 32],
+    pub voting_mints: [VotingMintConfigV0; 4],
+    pub time_offset: i64,
+    pub bump: u8,
+    pub reserved2: [u8; 7],
+    pub reserved3: [u64; 11],
+}
+const_assert!(std::mem::size_of::<RegistrarV0>() == 5 * 32 + 4 * 152 + 8 + 1 + 95);
+const_assert!(std::mem::size_of::<RegistrarV0>() % 8 == 0);
+unsafe impl Zeroable for RegistrarV0 {}
+unsafe impl Pod for RegistrarV0 {}
+
+impl RegistrarV0 {
+    /// Reads the legacy layout from account data, including the discriminator.
+    pub fn from_account_data(data: &[u8]) -> Self {
+        bytemuck::pod_read_unaligned(&data[8..8 + std::mem::size_of::<Self>()])
+    }
+
+    /// The registrar in the current layout. New fields are zero, which is
+    /// the default for all of them.
+    pub fn migrate(&self) -> Registrar {
+        let mut registrar = Registrar {
+            governance_program_id: self.governance_program_id,
+            realm: self.realm,
+            realm_governing_token_mint: self.realm_governing_token_mint,
+            realm_authority: self.realm_authority,
+            time_offset: self.time_offset,
+            bump: self.bump,
+            ..Registrar::default()
+        };
+        for (config, legacy_config) in registrar
+            .voting_mints
+            .iter_mut()
+            .zip(self.voting_mints.iter())
+        {
+            *config = legacy_config.migrate();
+        }
+        registrar
+    }
+}
+
+/// Layout of DepositEntry up to v0.2.4.
+#[zero_copy]
+pub struct DepositEntryV0 {
+    pub lockup: Lockup,
+    pub amount_deposited_native: u64,
+    pub amount_initially_locked_native: u64,
+    pub is_used: bool,
+    pub allow_clawback: bool,
+    pub voting_mint_config_idx: u8,
+    pub reserved: [u8; 29],
+}
+const_assert!(std::mem::size_of::<DepositEntryV0>() == 32 + 2 * 8 + 3 + 29);
+const_assert!(std::mem::size_of::<DepositEntryV0>() % 8 == 0);
+
+impl DepositEntryV0 {
+    /// The deposit entry in the current layout. New fields are zero.
+    pub fn migrate(&self) -> DepositEntry {
+        DepositEntry {
+            lockup: self.lockup,
+            amount_deposited_native: self.amount_deposited_native,
+            amount_initially_locked_native: self.amount_initially_locked_native,
+            is_used: self.is_used,
+            allow_clawback: self.allow_clawback,
+            voting_mint_config_idx: self.voting_mint_config_idx,
+            ..DepositEntry::default()
+        }
+    }
+}
+
+/// Layout of Voter up to v0.2.4, without the account discriminator.
+///
+/// Used by migrate_voter to convert voters created by these versions.
+#[zero_copy]
+pub struct VoterV0 {
+    pub voter_authority: Pubkey,
+    pub registrar: Pubkey,
+    pub deposits: [DepositEntryV0; 32],
+    pub voter_bump: u8,
+    pub voter_weight_record_bump: u8,
+    pub reserved: [u8; 94],
+}
+const_assert!(std::mem::size_of::<VoterV0>() == 2 * 32 + 32 * 80 + 2 + 94);
+const_assert!(std::mem::size_of::<VoterV0>() % 8 == 0);
+unsafe impl Zeroable for VoterV0 {}
+unsafe impl Pod for VoterV0 {}
+
+impl VoterV0 {
+    /// Reads the legacy layout from account data, including the discriminator.
+    pub fn from_account_data(data: &[u8]) -> Self {
+        bytemuck::pod_read_unaligned(&data[8..8 + std::mem::size_of::<Self>()])
+    }
+
+    /// The voter in the current layout. New fields are zero: the voter has no
+    /// vote locks, no delegate and no voter weight checkpoints.
+    pub fn migrate(&self) -> Voter {
+        let mut voter: Voter = Zeroable::zeroed();
+        voter.voter_authority = self.voter_authority;
+        voter.registrar = self.registrar;
+        for (deposit, legacy_deposit) in voter.deposits.iter_mut().zip(self.deposits.iter()) {
+            *deposit = legacy_deposit.migrate();
+        }
+        voter.voter_bump = self.voter_bump;
+        voter.voter_weight_record_bump = self.voter_weight_record_bump;
+        voter
+    }
+}
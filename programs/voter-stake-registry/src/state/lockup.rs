@@ -34,7 +34,8 @@ pub struct Lockup {
     /// locked up!
     ///
     /// Similarly vote power computations don't care about start_ts and always
-    /// assume the full interval from now to end_ts.
+    /// assume the full interval from now to end_ts, unless the voting mint has
+    /// a lockup warmup configured.
     pub(crate) start_ts: i64,
 
     /// End of the lockup.
@@ -796,11 +797,17 @@ mod tests {
                 kind: t.kind,
                 reserved: [0u8; 15],
             },
-            reserved: [0; 29],
+            grantor: Pubkey::default(),
+            grant_promised_native: 0,
+            grant_funded_native: 0,
+            lock_delegate: Pubkey::default(),
+            delegate: Pubkey::default(),
+            reserved: [0; 13],
         };
         let curr_ts = start_ts + days_to_secs(t.curr_day);
-        let power = d.voting_power_locked(curr_ts, t.amount_deposited, MAX_SECS_LOCKED)?;
-        assert_eq!(power, t.expected_voting_power);
+        let power =
+            d.voting_power_locked(curr_ts, t.amount_deposited as u128, MAX_SECS_LOCKED, None)?;
+        assert_eq!(power, t.expected_voting_power as u128);
         Ok(())
     }
 
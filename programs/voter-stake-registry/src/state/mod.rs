@@ -1,11 +1,23 @@
 pub use deposit_entry::*;
+pub use deposit_totals_change::*;
+pub use legacy::*;
 pub use lockup::*;
 pub use registrar::*;
+pub use supply_exclusions::*;
+pub use vote_lock::*;
 pub use voter::*;
+pub use voter_weight_checkpoints::*;
+pub use voter_weight_snapshot::*;
 pub use voting_mint_config::*;
 
 mod deposit_entry;
+mod deposit_totals_change;
+mod legacy;
 mod lockup;
 mod registrar;
+mod supply_exclusions;
+mod vote_lock;
 mod voter;
+mod voter_weight_checkpoints;
+mod voter_weight_snapshot;
 mod voting_mint_config;
@@ -1,7 +1,11 @@
 use crate::error::*;
+use crate::state::supply_exclusions::SupplyExclusions;
+use crate::state::voter::Voter;
 use crate::state::voting_mint_config::VotingMintConfig;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use std::convert::TryFrom;
 
 /// Instance of a voting rights distributor.
 #[account(zero_copy)]
@@ -11,7 +15,10 @@ pub struct Registrar {
     pub realm: Pubkey,
     pub realm_governing_token_mint: Pubkey,
     pub realm_authority: Pubkey,
-    pub reserved1: [u8; 32],
+
+    /// Address of the SupplyExclusions account, or Pubkey::default() if
+    /// the full supply of all voting mints is considered circulating.
+    pub supply_exclusions: Pubkey,
 
     /// Storage for voting mints and their configuration.
     /// The length should be adjusted for one's use case.
@@ -20,12 +27,142 @@ pub struct Registrar {
     /// Debug only: time offset, to allow tests to move forward in time.
     pub time_offset: i64,
     pub bump: u8,
-    pub reserved2: [u8; 7],
-    pub reserved3: [u64; 11], // split because `Default` does not support [u8; 95]
+
+    /// How update_max_vote_weight computes the max vote weight.
+    pub max_vote_weight_source: MaxVoteWeightSource,
+
+    /// Number of digits to shift internal vote weights by before they are written
+    /// to VoterWeightRecords or used as max vote weight, applying a
+    /// 10^vote_weight_output_digit_shift factor. Usually zero or negative.
+    pub vote_weight_output_digit_shift: i8,
+
+    /// Whether clawback takes the votes of the voter into account.
+    pub clawback_policy: ClawbackPolicy,
+
+    /// The spl-governance VoterWeightRecord format that records are written in.
+    pub voter_weight_record_version: VoterWeightRecordVersion,
+
+    /// How long written VoterWeightRecords stay valid.
+    pub voter_weight_expiry: VoterWeightExpiry,
+
+    pub reserved2: [u8; 2],
+
+    /// Minimum remaining lockup in seconds that a deposit needs to count
+    /// towards the vote weight written for each VoterWeightAction, indexed by
+    /// the action. Zero means all deposits count.
+    pub voter_weight_action_min_lockup_secs: [u64; 5],
+
+    /// Number of slots written VoterWeightRecords stay valid for with
+    /// `VoterWeightExpiry::Slots`.
+    pub voter_weight_expiry_slots: u64,
+
+    /// Incremented whenever the deposit totals in the voting mint configs are
+    /// reset. Voters with a different Voter::deposit_totals_epoch are not part
+    /// of the totals until sync_deposit_totals is called for them.
+    pub deposit_totals_epoch: u64,
+
+    pub reserved3: [u64; 4], // split because `Default` does not support [u8; 87]
 }
-const_assert!(std::mem::size_of::<Registrar>() == 5 * 32 + 4 * 152 + 8 + 1 + 95);
+const_assert!(std::mem::size_of::<Registrar>() == 5 * 32 + 4 * 256 + 8 + 1 + 95);
 const_assert!(std::mem::size_of::<Registrar>() % 8 == 0);
 
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MaxVoteWeightSource {
+    /// Use the supply of all voting mints, with full lockup bonus.
+    Supply,
+
+    /// Use the tokens deposited into the registrar: the baseline vote weight
+    /// of all deposits plus the max extra lockup vote weight of locked deposits.
+    Deposits,
+}
+
+impl Default for MaxVoteWeightSource {
+    fn default() -> Self {
+        MaxVoteWeightSource::Supply
+    }
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ClawbackPolicy {
+    /// Clawback ignores the voter's votes. Clawed back tokens may stay counted
+    /// on proposals the voter already voted on.
+    IgnoreVotes,
+
+    /// Clawback of tokens with vote weight requires that the voter has no
+    /// unrelinquished votes and no vote locks.
+    RequireNoOutstandingVotes,
+}
+
+impl Default for ClawbackPolicy {
+    fn default() -> Self {
+        ClawbackPolicy::IgnoreVotes
+    }
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum VoterWeightRecordVersion {
+    /// The format of spl-governance v2, with the discriminator of
+    /// spl-governance-addin-api 0.1.1.
+    V2,
+
+    /// The format of spl-governance v3. The fields are the same, but the
+    /// discriminator is the first 8 bytes of sha256("account:VoterWeightRecord").
+    V3,
+}
+
+impl Default for VoterWeightRecordVersion {
+    fn default() -> Self {
+        VoterWeightRecordVersion::V2
+    }
+}
+
+impl VoterWeightRecordVersion {
+    pub const DISCRIMINATOR_V2: [u8; 8] =
+        spl_governance_addin_api::voter_weight::VoterWeightRecord::ACCOUNT_DISCRIMINATOR;
+    pub const DISCRIMINATOR_V3: [u8; 8] = [46, 249, 155, 75, 153, 248, 116, 9];
+
+    pub fn discriminator(&self) -> [u8; 8] {
+        match self {
+            VoterWeightRecordVersion::V2 => Self::DISCRIMINATOR_V2,
+            VoterWeightRecordVersion::V3 => Self::DISCRIMINATOR_V3,
+        }
+    }
+
+    pub fn from_discriminator(discriminator: &[u8; 8]) -> Option<Self> {
+        if *discriminator == Self::DISCRIMINATOR_V2 {
+            Some(VoterWeightRecordVersion::V2)
+        } else if *discriminator == Self::DISCRIMINATOR_V3 {
+            Some(VoterWeightRecordVersion::V3)
+        } else {
+            None
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum VoterWeightExpiry {
+    /// Records expire at the end of the slot they are written in, so they must
+    /// be updated in the same transaction they are used in.
+    CurrentSlot,
+
+    /// Records stay valid for `voter_weight_expiry_slots` slots after the slot
+    /// they are written in.
+    Slots,
+
+    /// Records don't expire. For realms that keep them up to date with a crank.
+    Never,
+}
+
+impl Default for VoterWeightExpiry {
+    fn default() -> Self {
+        VoterWeightExpiry::CurrentSlot
+    }
+}
+
 impl Registrar {
     pub fn clock_unix_timestamp(&self) -> i64 {
         Clock::get()
@@ -35,6 +172,37 @@ impl Registrar {
             .unwrap()
     }
 
+    /// The minimum remaining lockup that deposits need to count towards the
+    /// vote weight for `action`.
+    ///
+    /// Without an action the strictest requirement applies, because spl-governance
+    /// accepts such VoterWeightRecords for every action.
+    pub fn min_lockup_secs_for_action(&self, action: Option<VoterWeightAction>) -> u64 {
+        let min_lockup_secs = self.voter_weight_action_min_lockup_secs;
+        match action {
+            Some(action) => min_lockup_secs[action as usize],
+            None => min_lockup_secs.iter().copied().max().unwrap(),
+        }
+    }
+
+    /// The `voter_weight_expiry` for a VoterWeightRecord written in `curr_slot`.
+    pub fn voter_weight_record_expiry(&self, curr_slot: u64) -> Option<u64> {
+        match self.voter_weight_expiry {
+            VoterWeightExpiry::CurrentSlot => Some(curr_slot),
+            VoterWeightExpiry::Slots => {
+                Some(curr_slot.saturating_add(self.voter_weight_expiry_slots))
+            }
+            VoterWeightExpiry::Never => None,
+        }
+    }
+
+    /// Whether the deposit totals include the deposits of `voter`, so that
+    /// changes to them must be recorded, see DepositTotalsChange.
+    pub fn tracks_deposit_totals_of(&self, voter: &Voter) -> bool {
+        self.max_vote_weight_source == MaxVoteWeightSource::Deposits
+            && voter.deposit_totals_epoch == self.deposit_totals_epoch
+    }
+
     pub fn voting_mint_config_index(&self, mint: Pubkey) -> Result<usize> {
         self.voting_mints
             .iter()
@@ -42,10 +210,97 @@ impl Registrar {
             .ok_or_else(|| error!(VsrError::VotingMintNotFound))
     }
 
+    /// The config of an already configured voting mint, for instructions that
+    /// change one of its settings.
+    ///
+    /// `idx` is the index of the voting mint config and must be the index that
+    /// `mint` is configured at. Requiring both guards against changing the
+    /// wrong voting mint.
+    pub fn voting_mint_config_mut(
+        &mut self,
+        idx: u16,
+        mint: Pubkey,
+    ) -> Result<&mut VotingMintConfig> {
+        let idx = idx as usize;
+        require_gt!(
+            self.voting_mints.len(),
+            idx,
+            VsrError::OutOfBoundsVotingMintConfigIndex
+        );
+        require_eq!(
+            self.voting_mint_config_index(mint)?,
+            idx,
+            VsrError::VotingMintConfiguredWithDifferentIndex
+        );
+        Ok(&mut self.voting_mints[idx])
+    }
+
+    /// Converts an internal vote weight to the u64 vote weight used by spl-governance.
+    ///
+    /// Vote weights are computed as u128 to be able to support large supplies and
+    /// high precision factors at the same time. Applying the registrar's output digit
+    /// shift to them must produce a value that fits a u64.
+    pub fn vote_weight_output(&self, vote_weight: u128) -> Result<u64> {
+        let compute = || -> Option<u64> {
+            let shift = self.vote_weight_output_digit_shift;
+            let factor = 10u128.checked_pow(shift.unsigned_abs() as u32)?;
+            let val = if shift < 0 {
+                vote_weight.checked_div(factor)?
+            } else {
+                vote_weight.checked_mul(factor)?
+            };
+            u64::try_from(val).ok()
+        };
+        compute().ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+    }
+
+    /// The maximum vote weight that all tokens of the voting mints could produce.
+    ///
+    /// For voting mints with a factor schedule, this uses the largest factors
+    /// over the whole schedule.
     pub fn max_vote_weight(&self, mint_accounts: &[AccountInfo]) -> Result<u64> {
-        self.voting_mints
-            .iter()
-            .try_fold(0u64, |mut sum, voting_mint_config| -> Result<u64> {
+        self.max_vote_weight_impl(mint_accounts, None)
+    }
+
+    /// Like max_vote_weight(), but ignores the balances of the excluded token
+    /// accounts. These must be part of `accounts`, next to the mints.
+    pub fn max_vote_weight_circulating(
+        &self,
+        accounts: &[AccountInfo],
+        exclusions: &SupplyExclusions,
+    ) -> Result<u64> {
+        self.max_vote_weight_impl(accounts, Some(exclusions))
+    }
+
+    /// The maximum vote weight that the currently deposited tokens could produce.
+    ///
+    /// Based on the running totals in the voting mint configs, which only
+    /// include the voters that are synced to the current deposit_totals_epoch.
+    pub fn max_vote_weight_from_deposits(&self) -> Result<u64> {
+        let curr_ts = self.clock_unix_timestamp();
+        let max_vote_weight =
+            self.voting_mints
+                .iter()
+                .filter(|c| c.in_use())
+                .try_fold(0u128, |sum, c| {
+                    let baseline = c.baseline_vote_weight(c.deposited_native_total, curr_ts)?;
+                    let extra =
+                        c.max_extra_lockup_vote_weight(c.lockup_weighted_native_total, curr_ts)?;
+                    sum.checked_add(baseline)
+                        .and_then(|sum| sum.checked_add(extra))
+                        .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+                })?;
+        self.vote_weight_output(max_vote_weight)
+    }
+
+    fn max_vote_weight_impl(
+        &self,
+        mint_accounts: &[AccountInfo],
+        exclusions: Option<&SupplyExclusions>,
+    ) -> Result<u64> {
+        let max_vote_weight = self.voting_mints.iter().try_fold(
+            0u128,
+            |mut sum, voting_mint_config| -> Result<u128> {
                 if !voting_mint_config.in_use() {
                     return Ok(sum);
                 }
@@ -54,14 +309,24 @@ impl Registrar {
                     .find(|a| a.key() == voting_mint_config.mint)
                     .ok_or_else(|| error!(VsrError::VotingMintNotFound))?;
                 let mint = Account::<Mint>::try_from(mint_account)?;
+                let supply = match exclusions {
+                    Some(exclusions) => mint.supply.saturating_sub(
+                        exclusions.excluded_amount(&voting_mint_config.mint, mint_accounts)?,
+                    ),
+                    None => mint.supply,
+                };
                 sum = sum
-                    .checked_add(voting_mint_config.baseline_vote_weight(mint.supply)?)
+                    .checked_add(voting_mint_config.baseline_vote_weight_upper_bound(supply)?)
                     .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
                 sum = sum
-                    .checked_add(voting_mint_config.max_extra_lockup_vote_weight(mint.supply)?)
+                    .checked_add(
+                        voting_mint_config.max_extra_lockup_vote_weight_upper_bound(supply)?,
+                    )
                     .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
                 Ok(sum)
-            })
+            },
+        )?;
+        self.vote_weight_output(max_vote_weight)
     }
 }
 
@@ -0,0 +1,51 @@
+use crate::error::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+/// Maximum number of token accounts that can be excluded from the supply.
+pub const MAX_SUPPLY_EXCLUSIONS: usize = 16;
+
+/// Token accounts whose balances are not considered circulating supply.
+///
+/// Their balances are subtracted from the supply of their voting mint when
+/// computing the max vote weight. Typical examples are treasury accounts or
+/// accounts holding burned-but-not-closed tokens.
+#[account(zero_copy)]
+pub struct SupplyExclusions {
+    pub registrar: Pubkey,
+
+    /// Excluded token accounts. Unused slots are Pubkey::default().
+    pub token_accounts: [Pubkey; 16],
+
+    pub bump: u8,
+    pub reserved: [u8; 31],
+}
+const_assert!(std::mem::size_of::<SupplyExclusions>() == 32 + 16 * 32 + 1 + 31);
+const_assert!(std::mem::size_of::<SupplyExclusions>() % 8 == 0);
+
+impl SupplyExclusions {
+    /// Sum of the balances of all excluded token accounts for `mint`.
+    ///
+    /// All excluded token accounts must be present in `accounts`. Accounts that
+    /// were closed since they were excluded count as holding no tokens.
+    pub fn excluded_amount(&self, mint: &Pubkey, accounts: &[AccountInfo]) -> Result<u64> {
+        self.token_accounts
+            .iter()
+            .filter(|k| **k != Pubkey::default())
+            .try_fold(0u64, |sum, key| {
+                let token_account_info = accounts
+                    .iter()
+                    .find(|a| a.key() == *key)
+                    .ok_or_else(|| error!(VsrError::SupplyExclusionAccountNotFound))?;
+                if token_account_info.data_is_empty() {
+                    return Ok(sum);
+                }
+                let token_account = Account::<TokenAccount>::try_from(token_account_info)?;
+                if token_account.mint != *mint {
+                    return Ok(sum);
+                }
+                sum.checked_add(token_account.amount)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+            })
+    }
+}
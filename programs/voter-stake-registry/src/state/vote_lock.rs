@@ -0,0 +1,67 @@
+use crate::state::registrar::Registrar;
+use anchor_lang::prelude::*;
+use spl_governance::state::{token_owner_record, vote_record};
+
+/// Number of votes a voter's weight can be used in at the same time.
+pub const MAX_VOTE_LOCKS: usize = 8;
+
+/// Records that a voter's weight was made available for a vote on `proposal`
+/// by `governing_token_owner`, who may be the voter or its delegate.
+///
+/// While a voter has vote locks, its tokens can't be withdrawn. See
+/// release_vote_locks.
+#[zero_copy]
+#[derive(Default)]
+pub struct VoteLock {
+    pub proposal: Pubkey,
+    pub governing_token_owner: Pubkey,
+
+    /// The slot the voter weight record for the vote was written in.
+    pub slot: u64,
+}
+const_assert!(std::mem::size_of::<VoteLock>() == 2 * 32 + 8);
+
+impl VoteLock {
+    pub fn is_used(&self) -> bool {
+        self.proposal != Pubkey::default()
+    }
+
+    /// The address of the spl-governance VoteRecord of the vote.
+    pub fn vote_record_address(&self, registrar: &Registrar) -> Pubkey {
+        let token_owner_record = token_owner_record::get_token_owner_record_address(
+            &registrar.governance_program_id,
+            &registrar.realm,
+            &registrar.realm_governing_token_mint,
+            &self.governing_token_owner,
+        );
+        vote_record::get_vote_record_address(
+            &registrar.governance_program_id,
+            &self.proposal,
+            &token_owner_record,
+        )
+    }
+
+    /// Whether the weight is no longer in use, given the account at
+    /// vote_record_address().
+    ///
+    /// That's the case if the vote was relinquished, or if no vote was cast
+    /// while the voter weight record was valid.
+    pub fn is_releasable(
+        &self,
+        vote_record_info: &AccountInfo,
+        registrar: &Registrar,
+        curr_slot: u64,
+    ) -> Result<bool> {
+        if vote_record_info.data_is_empty() {
+            // Either never voted or relinquished while voting was ongoing.
+            return Ok(curr_slot > self.slot);
+        }
+        let record = vote_record::get_vote_record_data_for_proposal_and_token_owner(
+            &registrar.governance_program_id,
+            vote_record_info,
+            &self.proposal,
+            &self.governing_token_owner,
+        )?;
+        Ok(record.is_relinquished)
+    }
+}
@@ -1,48 +1,123 @@
 use crate::error::*;
 use crate::state::deposit_entry::DepositEntry;
 use crate::state::registrar::Registrar;
+use crate::state::vote_lock::VoteLock;
 use anchor_lang::prelude::*;
 use spl_governance::state::token_owner_record;
 
+/// The maximum number of delegating voters update_voter_weight_record_with_delegations
+/// accepts, bounded by transaction size and compute limits.
+pub const MAX_DELEGATORS: usize = 16;
+
 /// User account for minting voting rights.
 #[account(zero_copy)]
 pub struct Voter {
     pub voter_authority: Pubkey,
     pub registrar: Pubkey,
     pub deposits: [DepositEntry; 32],
+
+    /// The votes the voter's weight is currently used in.
+    pub vote_locks: [VoteLock; 8],
+
     pub voter_bump: u8,
     pub voter_weight_record_bump: u8,
-    pub reserved: [u8; 94],
+
+    /// Whether the voter has a VoterWeightCheckpoints account that
+    /// instructions changing the deposits must update.
+    pub has_voter_weight_checkpoints: bool,
+    pub voter_weight_checkpoints_bump: u8,
+
+    /// The voter authority of the voter this voter delegates its vote weight to,
+    /// Pubkey::default() if not delegating.
+    pub delegate: Pubkey,
+
+    pub reserved1: [u8; 4],
+
+    /// The Registrar::deposit_totals_epoch in which this voter's deposits
+    /// were added to the registrar's deposit totals.
+    pub deposit_totals_epoch: u64,
+
+    pub reserved: [u8; 48],
 }
-const_assert!(std::mem::size_of::<Voter>() == 2 * 32 + 32 * 80 + 2 + 94);
+const_assert!(std::mem::size_of::<Voter>() == 3 * 32 + 32 * 176 + 4 + 8 * 72 + 4 + 8 + 48);
 const_assert!(std::mem::size_of::<Voter>() % 8 == 0);
 
 impl Voter {
     /// The full vote weight available to the voter
+    ///
+    /// Deposit entries delegated to other voters don't count, here and in
+    /// the other weight functions.
     pub fn weight(&self, registrar: &Registrar) -> Result<u64> {
-        let curr_ts = registrar.clock_unix_timestamp();
+        registrar.vote_weight_output(self.weight_internal(registrar)?)
+    }
+
+    /// The full vote weight available to the voter, before the registrar's
+    /// output scaling is applied
+    pub fn weight_internal(&self, registrar: &Registrar) -> Result<u128> {
+        self.weight_internal_at(registrar, registrar.clock_unix_timestamp())
+    }
+
+    fn weight_internal_at(&self, registrar: &Registrar, ts: i64) -> Result<u128> {
         self.deposits
             .iter()
-            .filter(|d| d.is_used)
-            .try_fold(0u64, |sum, d| {
-                d.voting_power(
+            .filter(|d| d.is_used && !d.is_delegated())
+            .try_fold(0u128, |sum, d| {
+                let vp = d.voting_power(
                     &registrar.voting_mints[d.voting_mint_config_idx as usize],
-                    curr_ts,
-                )
-                .map(|vp| sum.checked_add(vp).unwrap())
+                    ts,
+                )?;
+                sum.checked_add(vp)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
             })
     }
 
+    /// The vote weight of the current deposits as of `ts`, including the decay
+    /// of their lockups until then
+    ///
+    /// This is the voter's weight at `ts` only if the deposits don't change in
+    /// between, see VoterWeightCheckpoints::voter_weight_at().
+    pub fn weight_of_deposits_at(&self, registrar: &Registrar, ts: i64) -> Result<u64> {
+        registrar.vote_weight_output(self.weight_internal_at(registrar, ts)?)
+    }
+
+    /// The vote weight of the deposits that stay locked for at least `min_lockup_secs`
+    pub fn weight_with_min_lockup(
+        &self,
+        registrar: &Registrar,
+        min_lockup_secs: u64,
+    ) -> Result<u64> {
+        let curr_ts = registrar.clock_unix_timestamp();
+        let weight = self
+            .deposits
+            .iter()
+            .filter(|d| {
+                d.is_used && !d.is_delegated() && d.lockup.seconds_left(curr_ts) >= min_lockup_secs
+            })
+            .try_fold(0u128, |sum, d| {
+                let vp = d.voting_power(
+                    &registrar.voting_mints[d.voting_mint_config_idx as usize],
+                    curr_ts,
+                )?;
+                sum.checked_add(vp)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+            })?;
+        registrar.vote_weight_output(weight)
+    }
+
     /// The vote weight available to the voter when ignoring any lockup effects
     pub fn weight_baseline(&self, registrar: &Registrar) -> Result<u64> {
-        self.deposits
+        let curr_ts = registrar.clock_unix_timestamp();
+        let weight = self
+            .deposits
             .iter()
-            .filter(|d| d.is_used)
-            .try_fold(0u64, |sum, d| {
-                registrar.voting_mints[d.voting_mint_config_idx as usize]
-                    .baseline_vote_weight(d.amount_deposited_native)
-                    .map(|vp| sum.checked_add(vp).unwrap())
-            })
+            .filter(|d| d.is_used && !d.is_delegated())
+            .try_fold(0u128, |sum, d| {
+                let vp = registrar.voting_mints[d.voting_mint_config_idx as usize]
+                    .baseline_vote_weight(d.amount_deposited_native, curr_ts)?;
+                sum.checked_add(vp)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+            })?;
+        registrar.vote_weight_output(weight)
     }
 
     /// The extra lockup vote weight that the user is guaranteed to have at `at_ts`, assuming
@@ -54,21 +129,25 @@ impl Voter {
         at_ts: i64,
     ) -> Result<u64> {
         require_gte!(at_ts, curr_ts, VsrError::InvalidTimestampArguments);
-        self.deposits
+        let weight = self
+            .deposits
             .iter()
-            .filter(|d| d.is_used)
-            .try_fold(0u64, |sum, d| {
+            .filter(|d| d.is_used && !d.is_delegated())
+            .try_fold(0u128, |sum, d| {
                 let mint_config = &registrar.voting_mints[d.voting_mint_config_idx as usize];
-                let max_locked_vote_weight =
-                    mint_config.max_extra_lockup_vote_weight(d.amount_initially_locked_native)?;
+                let max_locked_vote_weight = mint_config
+                    .max_extra_lockup_vote_weight(d.amount_initially_locked_native, at_ts)?;
                 let amount = d.voting_power_locked_guaranteed(
                     curr_ts,
                     at_ts,
                     max_locked_vote_weight,
                     mint_config.lockup_saturation_secs,
+                    mint_config.lockup_warmup_factor(),
                 )?;
-                Ok(sum.checked_add(amount).unwrap())
-            })
+                sum.checked_add(amount)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+            })?;
+        registrar.vote_weight_output(weight)
     }
 
     pub fn active_deposit_mut(&mut self, index: u8) -> Result<&mut DepositEntry> {
@@ -83,6 +162,132 @@ impl Voter {
         Ok(d)
     }
 
+    /// The number of votes the voter's weight is used in, see VoteLock.
+    pub fn active_votes(&self) -> usize {
+        let vote_locks = self.vote_locks;
+        vote_locks.iter().filter(|l| l.is_used()).count()
+    }
+
+    /// Records that the voter's weight is made available to
+    /// `governing_token_owner` for voting on `proposal`.
+    pub fn add_vote_lock(
+        &mut self,
+        proposal: Pubkey,
+        governing_token_owner: Pubkey,
+        slot: u64,
+    ) -> Result<()> {
+        let vote_locks = self.vote_locks;
+        let index = vote_locks
+            .iter()
+            .position(|l| {
+                l.proposal == proposal && l.governing_token_owner == governing_token_owner
+            })
+            .or_else(|| vote_locks.iter().position(|l| !l.is_used()))
+            .ok_or_else(|| error!(VsrError::TooManyVoteLocks))?;
+        self.vote_locks[index] = VoteLock {
+            proposal,
+            governing_token_owner,
+            slot,
+        };
+        Ok(())
+    }
+
+    /// Removes the vote lock for a vote that was relinquished, if there is one.
+    pub fn remove_vote_lock(&mut self, proposal: Pubkey, governing_token_owner: Pubkey) {
+        let vote_locks = self.vote_locks;
+        if let Some(index) = vote_locks.iter().position(|l| {
+            l.proposal == proposal && l.governing_token_owner == governing_token_owner
+        }) {
+            self.vote_locks[index] = VoteLock::default();
+        }
+    }
+
+    /// Checks that the voter's weight isn't used in any vote, no matter
+    /// which token owner record cast it.
+    pub fn assert_no_active_votes(&self) -> Result<()> {
+        require_eq!(self.active_votes(), 0, VsrError::VoterHasOutstandingVotes);
+        Ok(())
+    }
+
+    pub fn is_delegating(&self) -> bool {
+        self.delegate != Pubkey::default()
+    }
+
+    /// The vote weight to write to the voter's own VoterWeightRecord: zero while
+    /// delegating, since the delegate uses it.
+    pub fn own_record_weight(&self, registrar: &Registrar, min_lockup_secs: u64) -> Result<u64> {
+        if self.is_delegating() {
+            return Ok(0);
+        }
+        self.weight_with_min_lockup(registrar, min_lockup_secs)
+    }
+
+    /// Checks that the delegate, if any, can't be using the voter's tokens in
+    /// an ongoing vote.
+    ///
+    /// The delegate's token owner record must be in `accounts`.
+    pub fn assert_delegate_can_withdraw(
+        &self,
+        accounts: &[AccountInfo],
+        registrar: &Registrar,
+    ) -> Result<()> {
+        if !self.is_delegating() {
+            return Ok(());
+        }
+        assert_no_outstanding_votes(&self.delegate, accounts, registrar)
+    }
+
+    /// Checks that the voter that a deposit entry is delegated to, if any, can't
+    /// be using its tokens in an ongoing vote.
+    ///
+    /// The delegate's Voter account and its token owner record must be in `accounts`.
+    pub fn assert_entry_delegate_can_withdraw(
+        &self,
+        deposit_entry_index: u8,
+        accounts: &[AccountInfo],
+        registrar: &Registrar,
+    ) -> Result<()> {
+        let d = match self.deposits.get(deposit_entry_index as usize) {
+            Some(d) if d.is_delegated() => d,
+            _ => return Ok(()),
+        };
+        let delegate_info = accounts
+            .iter()
+            .find(|a| a.key() == d.delegate)
+            .ok_or_else(|| error!(VsrError::InvalidDelegate))?;
+        let delegate_loader = AccountLoader::<Voter>::try_from(delegate_info)?;
+        let delegate_authority = delegate_loader.load()?.voter_authority;
+        assert_no_outstanding_votes(&delegate_authority, accounts, registrar)
+    }
+
+    /// The vote weight of the deposit entries delegated to the Voter account at
+    /// `delegate` that stay locked for at least `min_lockup_secs`
+    pub fn delegated_weight_with_min_lockup(
+        &self,
+        delegate: &Pubkey,
+        registrar: &Registrar,
+        min_lockup_secs: u64,
+    ) -> Result<u64> {
+        let curr_ts = registrar.clock_unix_timestamp();
+        let weight = self
+            .deposits
+            .iter()
+            .filter(|d| {
+                d.is_used
+                    && d.delegate == *delegate
+                    && d.lockup.seconds_left(curr_ts) >= min_lockup_secs
+            })
+            .try_fold(0u128, |sum, d| {
+                let vp = d.voting_power(
+                    &registrar.voting_mints[d.voting_mint_config_idx as usize],
+                    curr_ts,
+                )?;
+                sum.checked_add(vp)
+                    .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+            })?;
+        registrar.vote_weight_output(weight)
+    }
+
     pub fn load_token_owner_record(
         &self,
         account_info: &AccountInfo,
@@ -116,3 +321,30 @@ macro_rules! voter_seeds {
 }
 
 pub use voter_seeds;
+
+/// Checks that the token owner record of `governing_token_owner`, which must
+/// be in `accounts`, has no outstanding votes.
+fn assert_no_outstanding_votes(
+    governing_token_owner: &Pubkey,
+    accounts: &[AccountInfo],
+    registrar: &Registrar,
+) -> Result<()> {
+    let address = token_owner_record::get_token_owner_record_address(
+        &registrar.governance_program_id,
+        &registrar.realm,
+        &registrar.realm_governing_token_mint,
+        governing_token_owner,
+    );
+    let account_info = accounts
+        .iter()
+        .find(|a| a.key() == address)
+        .ok_or_else(|| error!(VsrError::InvalidTokenOwnerRecord))?;
+    let record = token_owner_record::get_token_owner_record_data_for_realm_and_governing_mint(
+        &registrar.governance_program_id,
+        account_info,
+        &registrar.realm,
+        &registrar.realm_governing_token_mint,
+    )?;
+    record.assert_can_withdraw_governing_tokens()?;
+    Ok(())
+}
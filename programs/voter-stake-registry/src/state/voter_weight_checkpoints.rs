@@ -0,0 +1,180 @@
+use crate::error::*;
+use crate::state::registrar::Registrar;
+use crate::state::voter::Voter;
+use anchor_lang::prelude::*;
+
+/// Number of checkpoints kept per voter.
+pub const MAX_VOTER_WEIGHT_CHECKPOINTS: usize = 64;
+
+/// The vote weight of a voter after a change at `ts`.
+#[zero_copy]
+#[derive(Default)]
+pub struct VoterWeightCheckpoint {
+    pub ts: i64,
+    pub weight: u64,
+}
+const_assert!(std::mem::size_of::<VoterWeightCheckpoint>() == 16);
+
+/// History of a voter's vote weight.
+///
+/// A ring buffer of the vote weight after each instruction that changed the
+/// voter's deposits, see record_voter_weight_checkpoint().
+///
+/// Seeds: [voter, b"voter-weight-checkpoints"]
+#[account(zero_copy)]
+pub struct VoterWeightCheckpoints {
+    pub voter: Pubkey,
+
+    /// Checkpoints in chronological order, starting at (head - len).
+    pub checkpoints: [VoterWeightCheckpoint; 64],
+
+    /// Index where the next checkpoint is written.
+    pub head: u16,
+
+    /// Number of used checkpoints.
+    pub len: u16,
+
+    pub bump: u8,
+    pub reserved: [u8; 27],
+}
+const_assert!(std::mem::size_of::<VoterWeightCheckpoints>() == 32 + 64 * 16 + 2 + 2 + 1 + 27);
+const_assert!(std::mem::size_of::<VoterWeightCheckpoints>() % 8 == 0);
+
+impl VoterWeightCheckpoints {
+    /// The i-th oldest checkpoint.
+    fn checkpoint(&self, i: usize) -> VoterWeightCheckpoint {
+        let first = self.head as usize + MAX_VOTER_WEIGHT_CHECKPOINTS - self.len as usize;
+        self.checkpoints[(first + i) % MAX_VOTER_WEIGHT_CHECKPOINTS]
+    }
+
+    /// Appends a checkpoint, replacing the oldest one if the buffer is full.
+    ///
+    /// A checkpoint with the same `ts` as the latest one replaces it.
+    pub fn push(&mut self, ts: i64, weight: u64) {
+        let len = self.len as usize;
+        if len > 0 && self.checkpoint(len - 1).ts == ts {
+            let last = (self.head as usize + MAX_VOTER_WEIGHT_CHECKPOINTS - 1)
+                % MAX_VOTER_WEIGHT_CHECKPOINTS;
+            self.checkpoints[last].weight = weight;
+            return;
+        }
+
+        self.checkpoints[self.head as usize] = VoterWeightCheckpoint { ts, weight };
+        self.head = ((self.head as usize + 1) % MAX_VOTER_WEIGHT_CHECKPOINTS) as u16;
+        self.len = (len + 1).min(MAX_VOTER_WEIGHT_CHECKPOINTS) as u16;
+    }
+
+    /// The weight of the latest checkpoint at or before `ts`.
+    ///
+    /// None if `ts` is before the oldest checkpoint.
+    pub fn weight_at(&self, ts: i64) -> Option<u64> {
+        // binary search for the number of checkpoints with checkpoint.ts <= ts
+        let (mut lo, mut hi) = (0, self.len as usize);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if self.checkpoint(mid).ts <= ts {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        (lo > 0).then(|| self.checkpoint(lo - 1).weight)
+    }
+
+    /// The voter's vote weight at `ts`.
+    ///
+    /// The deposits didn't change since the latest checkpoint, so from then on
+    /// the weight is computed from the current deposits, including the decay of
+    /// their lockups until `ts`. Before that it is the weight of the latest
+    /// checkpoint at or before `ts`: older deposits aren't kept, so the decay
+    /// between two checkpoints isn't included.
+    ///
+    /// None if `ts` is before the oldest checkpoint.
+    pub fn voter_weight_at(
+        &self,
+        voter: &Voter,
+        registrar: &Registrar,
+        ts: i64,
+    ) -> Result<Option<u64>> {
+        let len = self.len as usize;
+        if len > 0 && self.checkpoint(len - 1).ts <= ts {
+            return voter.weight_of_deposits_at(registrar, ts).map(Some);
+        }
+        Ok(self.weight_at(ts))
+    }
+}
+
+/// Finds the voter's VoterWeightCheckpoints account in `accounts`.
+pub fn find_voter_weight_checkpoints<'a, 'info>(
+    voter_key: &Pubkey,
+    voter: &Voter,
+    accounts: &'a [AccountInfo<'info>],
+    program_id: &Pubkey,
+) -> Result<&'a AccountInfo<'info>> {
+    let address = Pubkey::create_program_address(
+        &[
+            voter_key.as_ref(),
+            b"voter-weight-checkpoints".as_ref(),
+            &[voter.voter_weight_checkpoints_bump],
+        ],
+        program_id,
+    )
+    .unwrap();
+    accounts
+        .iter()
+        .find(|a| a.key() == address)
+        .ok_or_else(|| error!(VsrError::VoterWeightCheckpointsNotFound))
+}
+
+/// Appends the voter's current vote weight to its checkpoints.
+///
+/// Does nothing for voters without checkpoints. Otherwise their
+/// VoterWeightCheckpoints account must be in `accounts` and writable.
+pub fn record_voter_weight_checkpoint(
+    voter_key: &Pubkey,
+    voter: &Voter,
+    registrar: &Registrar,
+    accounts: &[AccountInfo],
+    program_id: &Pubkey,
+) -> Result<()> {
+    if !voter.has_voter_weight_checkpoints {
+        return Ok(());
+    }
+
+    let info = find_voter_weight_checkpoints(voter_key, voter, accounts, program_id)?;
+    let loader = AccountLoader::<VoterWeightCheckpoints>::try_from(info)?;
+    let mut checkpoints = loader.load_mut()?;
+    checkpoints.push(registrar.clock_unix_timestamp(), voter.weight(registrar)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn checkpoints_ring_buffer() {
+        let mut checkpoints: VoterWeightCheckpoints = bytemuck::Zeroable::zeroed();
+        assert_eq!(checkpoints.weight_at(100), None);
+
+        checkpoints.push(100, 1);
+        checkpoints.push(200, 2);
+        checkpoints.push(200, 3);
+        assert_eq!(checkpoints.len, 2);
+        assert_eq!(checkpoints.weight_at(99), None);
+        assert_eq!(checkpoints.weight_at(100), Some(1));
+        assert_eq!(checkpoints.weight_at(199), Some(1));
+        assert_eq!(checkpoints.weight_at(200), Some(3));
+        assert_eq!(checkpoints.weight_at(1000), Some(3));
+
+        // wrap around, dropping the oldest checkpoints
+        for i in 0..MAX_VOTER_WEIGHT_CHECKPOINTS as i64 {
+            checkpoints.push(300 + i, 300 + i as u64);
+        }
+        assert_eq!(checkpoints.len as usize, MAX_VOTER_WEIGHT_CHECKPOINTS);
+        assert_eq!(checkpoints.weight_at(299), None);
+        assert_eq!(checkpoints.weight_at(300), Some(300));
+        assert_eq!(checkpoints.weight_at(340), Some(340));
+        assert_eq!(checkpoints.weight_at(10000), Some(363));
+    }
+}
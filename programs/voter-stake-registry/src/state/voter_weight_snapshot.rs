@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// The vote weight of a voter for a single proposal, fixed at the time
+/// the proposal started.
+///
+/// Seeds: [voter, b"voter-weight-snapshot", proposal]
+#[account(zero_copy)]
+pub struct VoterWeightSnapshot {
+    pub registrar: Pubkey,
+    pub voter: Pubkey,
+    pub proposal: Pubkey,
+
+    /// The time the vote weight was computed for: the proposal's voting_at,
+    /// or its draft_at if voting had not started.
+    pub snapshot_ts: i64,
+
+    /// The vote weight as of snapshot_ts, see
+    /// VoterWeightCheckpoints::voter_weight_at().
+    pub voter_weight: u64,
+
+    pub bump: u8,
+    pub reserved: [u8; 31],
+}
+const_assert!(std::mem::size_of::<VoterWeightSnapshot>() == 3 * 32 + 8 + 8 + 1 + 31);
+const_assert!(std::mem::size_of::<VoterWeightSnapshot>() % 8 == 0);
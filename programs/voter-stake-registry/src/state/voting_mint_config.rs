@@ -20,6 +20,8 @@ pub struct VotingMintConfig {
 
     /// Vote weight factor for all funds in the account, no matter if locked or not.
     ///
+    /// If a factor schedule is set, this is the factor before factor_schedule_start_ts.
+    ///
     /// In 1/SCALED_FACTOR_BASE units.
     pub baseline_vote_weight_scaled_factor: u64,
 
@@ -29,6 +31,8 @@ pub struct VotingMintConfig {
     /// longer. Shorter lockups receive only a fraction of the maximum extra vote weight,
     /// based on lockup_time divided by lockup_saturation_secs.
     ///
+    /// If a factor schedule is set, this is the factor before factor_schedule_start_ts.
+    ///
     /// In 1/SCALED_FACTOR_BASE units.
     pub max_extra_lockup_vote_weight_scaled_factor: u64,
 
@@ -38,62 +42,272 @@ pub struct VotingMintConfig {
     /// Number of digits to shift native amounts, applying a 10^digit_shift factor.
     pub digit_shift: i8,
 
+    /// If true, deposits whose lockup has not started yet only receive
+    /// pre_start_lockup_bonus_scaled_factor of their extra lockup vote weight.
+    pub lockup_warmup_enabled: bool,
+
     // Empty bytes for future upgrades.
-    pub reserved1: [u8; 7],
-    pub reserved2: [u64; 7], // split because `Default` does not support [u8; 63]
+    pub reserved1: [u8; 6],
+
+    /// Baseline vote weight factor reached at factor_schedule_end_ts.
+    ///
+    /// In 1/SCALED_FACTOR_BASE units.
+    pub baseline_vote_weight_scaled_factor_end: u64,
+
+    /// Maximum extra lockup vote weight factor reached at factor_schedule_end_ts.
+    ///
+    /// In 1/SCALED_FACTOR_BASE units.
+    pub max_extra_lockup_vote_weight_scaled_factor_end: u64,
+
+    /// Start of the linear transition from the factors to the _end factors.
+    pub factor_schedule_start_ts: i64,
+
+    /// End of the linear transition from the factors to the _end factors.
+    ///
+    /// There is no schedule if this is not larger than factor_schedule_start_ts.
+    pub factor_schedule_end_ts: i64,
+
+    /// Sum of amount_deposited_native over all deposit entries of this mint.
+    ///
+    /// Only includes voters that are synced to the registrar's
+    /// deposit_totals_epoch.
+    pub deposited_native_total: u64,
+
+    /// Sum of DepositEntry::amount_lockup_weighted_upper_bound() over all
+    /// deposit entries of this mint.
+    ///
+    /// Only includes voters that are synced to the registrar's
+    /// deposit_totals_epoch.
+    pub lockup_weighted_native_total: u64,
+
+    /// Fraction of the extra lockup vote weight that deposits receive before
+    /// their lockup start_ts, if lockup_warmup_enabled.
+    ///
+    /// In 1/SCALED_FACTOR_BASE units, at most SCALED_FACTOR_BASE.
+    pub pre_start_lockup_bonus_scaled_factor: u64,
+
+    /// Token account that receives the penalties paid by unlock_early.
+    ///
+    /// Unlocking early is disabled if this is Pubkey::default().
+    pub unlock_early_penalty_destination: Pubkey,
+
+    /// Penalty for unlocking tokens early that would stay locked for
+    /// lockup_saturation_secs or longer. Tokens with a shorter remaining
+    /// lockup pay a proportional fraction of it.
+    ///
+    /// In 1/SCALED_FACTOR_BASE units, at most SCALED_FACTOR_BASE.
+    pub unlock_early_max_penalty_scaled_factor: u64,
+
+    /// Token account that clawed back tokens must be sent to.
+    ///
+    /// Any token account is allowed if this is Pubkey::default().
+    pub clawback_destination: Pubkey,
+
+    /// The authority that is allowed to claw back tokens from deposit
+    /// entries created by grant, in addition to the realm authority
+    pub clawback_authority: Pubkey,
 }
-const_assert!(std::mem::size_of::<VotingMintConfig>() == 2 * 32 + 3 * 8 + 1 + 63);
+const_assert!(std::mem::size_of::<VotingMintConfig>() == 5 * 32 + 11 * 8 + 2 + 6);
 const_assert!(std::mem::size_of::<VotingMintConfig>() % 8 == 0);
 
 impl VotingMintConfig {
     /// Converts an amount in this voting mints's native currency
     /// to the base vote weight (without the deposit or lockup scalings)
     /// by applying the digit_shift factor.
-    fn digit_shift_native(&self, amount_native: u64) -> Result<u64> {
-        let compute = || -> Option<u64> {
-            let val = if self.digit_shift < 0 {
-                (amount_native as u128).checked_div(10u128.pow((-self.digit_shift) as u32))?
+    fn digit_shift_native(&self, amount_native: u64) -> Result<u128> {
+        let compute = || -> Option<u128> {
+            let factor = 10u128.checked_pow(self.digit_shift.unsigned_abs() as u32)?;
+            if self.digit_shift < 0 {
+                (amount_native as u128).checked_div(factor)
             } else {
-                (amount_native as u128).checked_mul(10u128.pow(self.digit_shift as u32))?
-            };
-            u64::try_from(val).ok()
+                (amount_native as u128).checked_mul(factor)
+            }
         };
         compute().ok_or_else(|| error!(VsrError::VoterWeightOverflow))
     }
 
     /// Apply a factor in SCALED_FACTOR_BASE units.
-    fn apply_factor(base: u64, factor: u64) -> Result<u64> {
-        let compute = || -> Option<u64> {
-            u64::try_from(
-                (base as u128)
-                    .checked_mul(factor as u128)?
-                    .checked_div(SCALED_FACTOR_BASE as u128)?,
-            )
-            .ok()
-        };
-        compute().ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+    pub fn apply_factor(base: u128, factor: u64) -> Result<u128> {
+        base.checked_mul(factor as u128)
+            .map(|v| v / SCALED_FACTOR_BASE as u128)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+    }
+
+    /// Whether the factors change over time.
+    pub fn has_factor_schedule(&self) -> bool {
+        self.factor_schedule_end_ts > self.factor_schedule_start_ts
+    }
+
+    /// Linearly interpolates between a start and end factor according
+    /// to the factor schedule.
+    fn scheduled_factor(&self, start: u64, end: u64, curr_ts: i64) -> u64 {
+        if !self.has_factor_schedule() || curr_ts <= self.factor_schedule_start_ts {
+            return start;
+        }
+        if curr_ts >= self.factor_schedule_end_ts {
+            return end;
+        }
+        let elapsed = (curr_ts - self.factor_schedule_start_ts) as u128;
+        let duration = (self.factor_schedule_end_ts - self.factor_schedule_start_ts) as u128;
+        // The result is always between start and end, the casts can't truncate.
+        if end >= start {
+            start + ((end - start) as u128 * elapsed / duration) as u64
+        } else {
+            start - ((start - end) as u128 * elapsed / duration) as u64
+        }
+    }
+
+    /// The baseline vote weight factor in effect at `curr_ts`.
+    pub fn baseline_vote_weight_scaled_factor_at(&self, curr_ts: i64) -> u64 {
+        self.scheduled_factor(
+            self.baseline_vote_weight_scaled_factor,
+            self.baseline_vote_weight_scaled_factor_end,
+            curr_ts,
+        )
+    }
+
+    /// The max extra lockup vote weight factor in effect at `curr_ts`.
+    pub fn max_extra_lockup_vote_weight_scaled_factor_at(&self, curr_ts: i64) -> u64 {
+        self.scheduled_factor(
+            self.max_extra_lockup_vote_weight_scaled_factor,
+            self.max_extra_lockup_vote_weight_scaled_factor_end,
+            curr_ts,
+        )
+    }
+
+    /// The largest baseline vote weight factor over the whole schedule.
+    pub fn baseline_vote_weight_scaled_factor_max(&self) -> u64 {
+        if self.has_factor_schedule() {
+            self.baseline_vote_weight_scaled_factor
+                .max(self.baseline_vote_weight_scaled_factor_end)
+        } else {
+            self.baseline_vote_weight_scaled_factor
+        }
+    }
+
+    /// The largest max extra lockup vote weight factor over the whole schedule.
+    pub fn max_extra_lockup_vote_weight_scaled_factor_max(&self) -> u64 {
+        if self.has_factor_schedule() {
+            self.max_extra_lockup_vote_weight_scaled_factor
+                .max(self.max_extra_lockup_vote_weight_scaled_factor_end)
+        } else {
+            self.max_extra_lockup_vote_weight_scaled_factor
+        }
     }
 
     /// The vote weight a deposit of a number of native tokens should have.
     ///
+    /// Vote weights are computed as u128 and only converted to u64 by
+    /// Registrar::vote_weight_output().
+    ///
     /// This vote_weight is a component for all funds in a voter account, no
     /// matter if locked up or not.
-    pub fn baseline_vote_weight(&self, amount_native: u64) -> Result<u64> {
+    pub fn baseline_vote_weight(&self, amount_native: u64, curr_ts: i64) -> Result<u128> {
         Self::apply_factor(
             self.digit_shift_native(amount_native)?,
-            self.baseline_vote_weight_scaled_factor,
+            self.baseline_vote_weight_scaled_factor_at(curr_ts),
         )
     }
 
     /// The maximum extra vote weight a number of locked up native tokens can have.
     /// Will be multiplied with a factor between 0 and 1 for the lockup duration.
-    pub fn max_extra_lockup_vote_weight(&self, amount_native: u64) -> Result<u64> {
+    pub fn max_extra_lockup_vote_weight(&self, amount_native: u64, curr_ts: i64) -> Result<u128> {
         Self::apply_factor(
             self.digit_shift_native(amount_native)?,
-            self.max_extra_lockup_vote_weight_scaled_factor,
+            self.max_extra_lockup_vote_weight_scaled_factor_at(curr_ts),
+        )
+    }
+
+    /// Upper bound for baseline_vote_weight() over the whole factor schedule.
+    pub fn baseline_vote_weight_upper_bound(&self, amount_native: u64) -> Result<u128> {
+        Self::apply_factor(
+            self.digit_shift_native(amount_native)?,
+            self.baseline_vote_weight_scaled_factor_max(),
+        )
+    }
+
+    /// Upper bound for max_extra_lockup_vote_weight() over the whole factor schedule.
+    pub fn max_extra_lockup_vote_weight_upper_bound(&self, amount_native: u64) -> Result<u128> {
+        Self::apply_factor(
+            self.digit_shift_native(amount_native)?,
+            self.max_extra_lockup_vote_weight_scaled_factor_max(),
         )
     }
 
+    /// The fraction of extra lockup vote weight deposits get before their lockup
+    /// starts, or None if they get the full extra lockup vote weight.
+    pub fn lockup_warmup_factor(&self) -> Option<u64> {
+        self.lockup_warmup_enabled
+            .then(|| self.pre_start_lockup_bonus_scaled_factor)
+    }
+
+    /// Checks that a pre-start lockup bonus factor is a fraction.
+    pub fn validate_lockup_warmup_factor(factor: u64) -> Result<()> {
+        require_gte!(
+            SCALED_FACTOR_BASE,
+            factor,
+            VsrError::InvalidLockupWarmupFactor
+        );
+        Ok(())
+    }
+
+    /// Whether locked tokens of this mint may be unlocked early.
+    pub fn unlock_early_enabled(&self) -> bool {
+        self.unlock_early_penalty_destination != Pubkey::default()
+    }
+
+    /// Checks that an unlock early penalty factor is a fraction.
+    pub fn validate_unlock_early_penalty_factor(factor: u64) -> Result<()> {
+        require_gte!(
+            SCALED_FACTOR_BASE,
+            factor,
+            VsrError::InvalidUnlockEarlyPenalty
+        );
+        Ok(())
+    }
+
+    /// The penalty for unlocking `amount_native` tokens that would stay locked for
+    /// another `seconds_left` seconds.
+    ///
+    /// Rounds up, so splitting an unlock into small pieces can't avoid the penalty.
+    /// Never exceeds `amount_native`.
+    pub fn unlock_early_penalty(&self, amount_native: u64, seconds_left: u64) -> u64 {
+        let div_ceil = |a: u128, b: u128| (a + b - 1) / b;
+        let remaining = seconds_left.min(self.lockup_saturation_secs);
+        let max_penalty = div_ceil(
+            amount_native as u128 * self.unlock_early_max_penalty_scaled_factor as u128,
+            SCALED_FACTOR_BASE as u128,
+        );
+        // max_penalty <= amount_native and remaining <= lockup_saturation_secs,
+        // so the result fits.
+        div_ceil(
+            max_penalty * remaining as u128,
+            self.lockup_saturation_secs as u128,
+        ) as u64
+    }
+
+    /// Adds signed changes to deposited_native_total and
+    /// lockup_weighted_native_total, see DepositTotalsChange.
+    ///
+    /// Decreases saturate at zero, so totals that got out of sync can't block
+    /// withdraws.
+    pub fn update_deposit_totals(
+        &mut self,
+        deposited_change: i128,
+        lockup_weighted_change: i128,
+    ) -> Result<()> {
+        let apply = |total: u64, change: i128| -> Result<u64> {
+            let compute = || -> Option<u64> {
+                u64::try_from(i128::from(total).checked_add(change)?.max(0)).ok()
+            };
+            compute().ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+        };
+        self.deposited_native_total = apply(self.deposited_native_total, deposited_change)?;
+        self.lockup_weighted_native_total =
+            apply(self.lockup_weighted_native_total, lockup_weighted_change)?;
+        Ok(())
+    }
+
     /// Whether this voting mint is configured.
     pub fn in_use(&self) -> bool {
         self.mint != Pubkey::default()
@@ -105,9 +319,91 @@ impl VotingMintConfig {
     /// want to use the grant / vesting / clawback functionality for non-voting
     /// tokens like USDC.
     pub fn grants_vote_weight(&self) -> bool {
-        self.baseline_vote_weight_scaled_factor > 0
-            || self.max_extra_lockup_vote_weight_scaled_factor > 0
+        self.baseline_vote_weight_scaled_factor_max() > 0
+            || self.max_extra_lockup_vote_weight_scaled_factor_max() > 0
     }
 }
 
 unsafe impl Zeroable for VotingMintConfig {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn factor_schedule() -> Result<()> {
+        let config = VotingMintConfig {
+            baseline_vote_weight_scaled_factor: 2_000_000_000,
+            max_extra_lockup_vote_weight_scaled_factor: 1_000_000_000,
+            baseline_vote_weight_scaled_factor_end: 0,
+            max_extra_lockup_vote_weight_scaled_factor_end: 3_000_000_000,
+            factor_schedule_start_ts: 1000,
+            factor_schedule_end_ts: 2000,
+            lockup_saturation_secs: 1,
+            ..VotingMintConfig::default()
+        };
+        assert!(config.has_factor_schedule());
+
+        let baseline = |ts| config.baseline_vote_weight(1000, ts).unwrap();
+        let extra = |ts| config.max_extra_lockup_vote_weight(1000, ts).unwrap();
+        assert_eq!(baseline(0), 2000);
+        assert_eq!(baseline(1000), 2000);
+        assert_eq!(baseline(1250), 1500);
+        assert_eq!(baseline(1500), 1000);
+        assert_eq!(baseline(2000), 0);
+        assert_eq!(baseline(5000), 0);
+        assert_eq!(extra(0), 1000);
+        assert_eq!(extra(1500), 2000);
+        assert_eq!(extra(1750), 2500);
+        assert_eq!(extra(5000), 3000);
+
+        assert_eq!(config.baseline_vote_weight_upper_bound(1000)?, 2000);
+        assert_eq!(config.max_extra_lockup_vote_weight_upper_bound(1000)?, 3000);
+
+        // without a schedule, the _end factors are ignored
+        let unscheduled = VotingMintConfig {
+            factor_schedule_end_ts: 1000,
+            ..config
+        };
+        assert!(!unscheduled.has_factor_schedule());
+        assert_eq!(unscheduled.baseline_vote_weight(1000, 5000)?, 2000);
+        assert_eq!(
+            unscheduled.max_extra_lockup_vote_weight_upper_bound(1000)?,
+            1000
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn unlock_early_penalty() -> Result<()> {
+        let config = VotingMintConfig {
+            lockup_saturation_secs: 1000,
+            unlock_early_max_penalty_scaled_factor: 500_000_000, // 50%
+            ..VotingMintConfig::default()
+        };
+        assert_eq!(config.unlock_early_penalty(1000, 0), 0);
+        assert_eq!(config.unlock_early_penalty(1000, 100), 50);
+        assert_eq!(config.unlock_early_penalty(1000, 1000), 500);
+        assert_eq!(config.unlock_early_penalty(1000, 5000), 500);
+        // rounds up
+        assert_eq!(config.unlock_early_penalty(1, 1), 1);
+        assert_eq!(config.unlock_early_penalty(3, 1000), 2);
+        assert_eq!(
+            config.unlock_early_penalty(u64::MAX, u64::MAX),
+            u64::MAX / 2 + 1
+        );
+
+        let full = VotingMintConfig {
+            unlock_early_max_penalty_scaled_factor: 1_000_000_000,
+            ..config
+        };
+        assert_eq!(full.unlock_early_penalty(1000, 1000), 1000);
+        assert_eq!(
+            full.unlock_early_penalty(u64::MAX, 999),
+            18_428_297_329_635_842_064
+        );
+
+        Ok(())
+    }
+}
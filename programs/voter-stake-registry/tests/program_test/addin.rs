@@ -6,6 +6,7 @@ use solana_sdk::{
     instruction::Instruction,
     signature::{Keypair, Signer},
 };
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
 
 use crate::*;
 
@@ -156,49 +157,138 @@ impl AddinCookie {
         VotingMintConfigCookie { mint: mint.clone() }
     }
 
-    pub async fn create_voter(
+    #[allow(dead_code)]
+    pub async fn configure_voting_mint_schedule(
         &self,
         registrar: &RegistrarCookie,
-        token_owner_record: &TokenOwnerRecordCookie,
         authority: &Keypair,
-        payer: &Keypair,
-    ) -> VoterCookie {
-        let (voter, voter_bump) = Pubkey::find_program_address(
-            &[
-                &registrar.address.to_bytes(),
-                b"voter".as_ref(),
-                &authority.pubkey().to_bytes(),
-            ],
-            &self.program_id,
+        index: u16,
+        voting_mint: &VotingMintConfigCookie,
+        baseline_vote_weight_scaled_factor_end: f64,
+        max_extra_lockup_vote_weight_scaled_factor_end: f64,
+        schedule_start_ts: i64,
+        schedule_end_ts: i64,
+        other_mints: Option<&[Pubkey]>,
+    ) -> std::result::Result<(), TransportError> {
+        let deposit_mint = voting_mint.mint.pubkey.unwrap();
+
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureVotingMintSchedule {
+                idx: index,
+                baseline_vote_weight_scaled_factor_end: (baseline_vote_weight_scaled_factor_end
+                    * 1e9) as u64,
+                max_extra_lockup_vote_weight_scaled_factor_end:
+                    (max_extra_lockup_vote_weight_scaled_factor_end * 1e9) as u64,
+                schedule_start_ts,
+                schedule_end_ts,
+            },
         );
-        let (voter_weight_record, voter_weight_record_bump) = Pubkey::find_program_address(
-            &[
-                &registrar.address.to_bytes(),
-                b"voter-weight-record".as_ref(),
-                &authority.pubkey().to_bytes(),
-            ],
-            &self.program_id,
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureVotingMintSchedule {
+                mint: deposit_mint,
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
         );
+        accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+            deposit_mint,
+            false,
+        ));
+        for mint in other_mints.unwrap_or(&[]) {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *mint, false,
+            ));
+        }
 
-        let data =
-            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::CreateVoter {
-                voter_bump,
-                voter_weight_record_bump,
-            });
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_lockup_warmup(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        index: u16,
+        voting_mint: &VotingMintConfigCookie,
+        pre_start_lockup_bonus_scaled_factor: Option<f64>,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureLockupWarmup {
+                idx: index,
+                pre_start_lockup_bonus_scaled_factor: pre_start_lockup_bonus_scaled_factor
+                    .map(|f| (f * 1e9) as u64),
+            },
+        );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::CreateVoter {
-                voter,
-                voter_weight_record,
+            &voter_stake_registry::accounts::ConfigureLockupWarmup {
+                mint: voting_mint.mint.pubkey.unwrap(),
                 registrar: registrar.address,
-                voter_authority: authority.pubkey(),
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_supply_exclusions(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        payer: &Keypair,
+        token_accounts: &[Pubkey],
+    ) -> std::result::Result<(), TransportError> {
+        let supply_exclusions = registrar.supply_exclusions_address(&self.program_id);
+
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureSupplyExclusions {
+                token_accounts: token_accounts.to_vec(),
+            },
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureSupplyExclusions {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+                supply_exclusions,
                 payer: payer.pubkey(),
                 system_program: solana_sdk::system_program::id(),
                 rent: solana_program::sysvar::rent::id(),
-                instructions: solana_program::sysvar::instructions::id(),
             },
             None,
         );
+        for token_account in token_accounts {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *token_account,
+                false,
+            ));
+        }
 
         let instructions = vec![Instruction {
             program_id: self.program_id,
@@ -213,51 +303,1839 @@ impl AddinCookie {
         self.solana
             .process_transaction(&instructions, Some(&[&signer1, &signer2]))
             .await
-            .unwrap();
+    }
 
-        VoterCookie {
-            address: voter,
-            authority: authority.pubkey(),
-            voter_weight_record,
-            token_owner_record: token_owner_record.address,
-        }
+    #[allow(dead_code)]
+    pub async fn configure_max_vote_weight_source(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        source: voter_stake_registry::state::MaxVoteWeightSource,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureMaxVoteWeightSource { source },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureMaxVoteWeightSource {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
     }
 
-    pub async fn create_deposit_entry(
+    #[allow(dead_code)]
+    pub async fn sync_deposit_totals(
         &self,
         registrar: &RegistrarCookie,
         voter: &VoterCookie,
-        voter_authority: &Keypair,
-        voting_mint: &VotingMintConfigCookie,
-        deposit_entry_index: u8,
-        lockup_kind: voter_stake_registry::state::LockupKind,
-        start_ts: Option<u64>,
-        periods: u32,
-        allow_clawback: bool,
     ) -> std::result::Result<(), TransportError> {
-        let vault = voter.vault_address(&voting_mint);
-
         let data = anchor_lang::InstructionData::data(
-            &voter_stake_registry::instruction::CreateDepositEntry {
-                deposit_entry_index,
-                kind: lockup_kind,
-                start_ts,
-                periods,
-                allow_clawback,
+            &voter_stake_registry::instruction::SyncDepositTotals {},
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::SyncDepositTotals {
+                registrar: registrar.address,
+                voter: voter.address,
             },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana.process_transaction(&instructions, None).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn migrate_registrar(
+        &self,
+        registrar: &RegistrarCookie,
+        payer: &Keypair,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::MigrateRegistrar {},
         );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::CreateDepositEntry {
-                vault,
+            &voter_stake_registry::accounts::MigrateRegistrar {
                 registrar: registrar.address,
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana
+            .process_transaction(&instructions, Some(&[payer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn migrate_voter(
+        &self,
+        voter: &VoterCookie,
+        payer: &Keypair,
+    ) -> std::result::Result<(), TransportError> {
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::MigrateVoter {});
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::MigrateVoter {
                 voter: voter.address,
-                voter_authority: voter_authority.pubkey(),
-                payer: voter_authority.pubkey(),
-                deposit_mint: voting_mint.mint.pubkey.unwrap(),
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana
+            .process_transaction(&instructions, Some(&[payer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_vote_weight_output(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        vote_weight_output_digit_shift: i8,
+        mints: &[Pubkey],
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureVoteWeightOutput {
+                vote_weight_output_digit_shift,
+            },
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureVoteWeightOutput {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+        for mint in mints {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *mint, false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_max_vote_weight(
+        &self,
+        registrar: &RegistrarCookie,
+        remaining_accounts: &[Pubkey],
+    ) -> std::result::Result<u64, TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::UpdateMaxVoteWeight {},
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::UpdateMaxVoteWeight {
+                registrar: registrar.address,
+                max_vote_weight_record: Pubkey::new_unique(),
+            },
+            None,
+        );
+        for account in remaining_accounts {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *account, false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana.process_transaction(&instructions, None).await?;
+
+        let data_log = self.solana.program_output().data;
+        let event =
+            deserialize_event::<voter_stake_registry::events::MaxVoteWeightInfo>(&data_log[0])
+                .unwrap();
+        Ok(event.max_vote_weight)
+    }
+
+    pub async fn create_voter(
+        &self,
+        registrar: &RegistrarCookie,
+        token_owner_record: &TokenOwnerRecordCookie,
+        authority: &Keypair,
+        payer: &Keypair,
+    ) -> VoterCookie {
+        let (voter, voter_bump) = Pubkey::find_program_address(
+            &[
+                &registrar.address.to_bytes(),
+                b"voter".as_ref(),
+                &authority.pubkey().to_bytes(),
+            ],
+            &self.program_id,
+        );
+        let (voter_weight_record, voter_weight_record_bump) = Pubkey::find_program_address(
+            &[
+                &registrar.address.to_bytes(),
+                b"voter-weight-record".as_ref(),
+                &authority.pubkey().to_bytes(),
+            ],
+            &self.program_id,
+        );
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::CreateVoter {
+                voter_bump,
+                voter_weight_record_bump,
+            });
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CreateVoter {
+                voter,
+                voter_weight_record,
+                voter_weight_checkpoints: Pubkey::find_program_address(
+                    &[voter.as_ref(), b"voter-weight-checkpoints".as_ref()],
+                    &self.program_id,
+                )
+                .0,
+                registrar: registrar.address,
+                voter_authority: authority.pubkey(),
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+                rent: solana_program::sysvar::rent::id(),
+                instructions: solana_program::sysvar::instructions::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&payer.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
+            .await
+            .unwrap();
+
+        VoterCookie {
+            address: voter,
+            authority: authority.pubkey(),
+            voter_weight_record,
+            token_owner_record: token_owner_record.address,
+        }
+    }
+
+    pub async fn create_deposit_entry(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voter_authority: &Keypair,
+        voting_mint: &VotingMintConfigCookie,
+        deposit_entry_index: u8,
+        lockup_kind: voter_stake_registry::state::LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::CreateDepositEntry {
+                deposit_entry_index,
+                kind: lockup_kind,
+                start_ts,
+                periods,
+                allow_clawback,
+            },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CreateDepositEntry {
+                vault,
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_authority: voter_authority.pubkey(),
+                payer: voter_authority.pubkey(),
+                deposit_mint: voting_mint.mint.pubkey.unwrap(),
+                system_program: solana_sdk::system_program::id(),
+                token_program: spl_token::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&voter_authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn deposit(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Deposit {
+                deposit_entry_index,
+                amount,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::Deposit {
+                registrar: registrar.address,
+                voter: voter.address,
+                vault: vault,
+                deposit_token: token_address,
+                deposit_authority: authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn grant(
+        &self,
+        registrar: &RegistrarCookie,
+        voter_authority: Pubkey,
+        voting_mint: &VotingMintConfigCookie,
+        lockup_kind: voter_stake_registry::state::LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+        amount: u64,
+        deposit_token: Pubkey,
+        token_authority: &Keypair,
+        grant_authority: &Keypair,
+    ) -> std::result::Result<VoterCookie, TransportError> {
+        self.grant_impl(
+            registrar,
+            voter_authority,
+            voting_mint,
+            lockup_kind,
+            start_ts,
+            periods,
+            allow_clawback,
+            amount,
+            false,
+            deposit_token,
+            token_authority,
+            grant_authority,
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn grant_funded_over_time(
+        &self,
+        registrar: &RegistrarCookie,
+        voter_authority: Pubkey,
+        voting_mint: &VotingMintConfigCookie,
+        lockup_kind: voter_stake_registry::state::LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+        amount_promised: u64,
+        deposit_token: Pubkey,
+        token_authority: &Keypair,
+        grant_authority: &Keypair,
+    ) -> std::result::Result<VoterCookie, TransportError> {
+        self.grant_impl(
+            registrar,
+            voter_authority,
+            voting_mint,
+            lockup_kind,
+            start_ts,
+            periods,
+            allow_clawback,
+            amount_promised,
+            true,
+            deposit_token,
+            token_authority,
+            grant_authority,
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    async fn grant_impl(
+        &self,
+        registrar: &RegistrarCookie,
+        voter_authority: Pubkey,
+        voting_mint: &VotingMintConfigCookie,
+        lockup_kind: voter_stake_registry::state::LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+        amount: u64,
+        fund_over_time: bool,
+        deposit_token: Pubkey,
+        token_authority: &Keypair,
+        grant_authority: &Keypair,
+    ) -> std::result::Result<VoterCookie, TransportError> {
+        let (voter, voter_bump) = Pubkey::find_program_address(
+            &[
+                &registrar.address.to_bytes(),
+                b"voter".as_ref(),
+                &voter_authority.to_bytes(),
+            ],
+            &self.program_id,
+        );
+        let (voter_weight_record, voter_weight_record_bump) = Pubkey::find_program_address(
+            &[
+                &registrar.address.to_bytes(),
+                b"voter-weight-record".as_ref(),
+                &voter_authority.to_bytes(),
+            ],
+            &self.program_id,
+        );
+        let voter_cookie = VoterCookie {
+            address: voter,
+            authority: voter_authority,
+            voter_weight_record,
+            token_owner_record: Pubkey::new_unique(), // don't have it
+        };
+        let vault = voter_cookie.vault_address(&voting_mint);
+
+        let data = anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Grant {
+            voter_bump,
+            voter_weight_record_bump,
+            kind: lockup_kind,
+            start_ts,
+            periods,
+            allow_clawback,
+            amount,
+            fund_over_time,
+        });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::Grant {
+                registrar: registrar.address,
+                voter,
+                voter_authority,
+                voter_weight_record,
+                vault,
+                deposit_token,
+                token_authority: token_authority.pubkey(),
+                grant_authority: grant_authority.pubkey(),
+                payer: token_authority.pubkey(),
+                deposit_mint: voting_mint.mint.pubkey.unwrap(),
+                system_program: solana_sdk::system_program::id(),
+                token_program: spl_token::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(&voter_cookie),
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&grant_authority.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&token_authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
+            .await?;
+
+        Ok(voter_cookie)
+    }
+
+    #[allow(dead_code)]
+    pub async fn grant_batch(
+        &self,
+        registrar: &RegistrarCookie,
+        grants: &[(Pubkey, u64)],
+        voting_mint: &VotingMintConfigCookie,
+        lockup_kind: voter_stake_registry::state::LockupKind,
+        start_ts: Option<u64>,
+        periods: u32,
+        allow_clawback: bool,
+        deposit_token: Pubkey,
+        token_authority: &Keypair,
+        grant_authority: &Keypair,
+    ) -> std::result::Result<Vec<VoterCookie>, TransportError> {
+        let voter_cookies = grants
+            .iter()
+            .map(|(voter_authority, _)| {
+                let (voter, _) = Pubkey::find_program_address(
+                    &[
+                        &registrar.address.to_bytes(),
+                        b"voter".as_ref(),
+                        &voter_authority.to_bytes(),
+                    ],
+                    &self.program_id,
+                );
+                let (voter_weight_record, _) = Pubkey::find_program_address(
+                    &[
+                        &registrar.address.to_bytes(),
+                        b"voter-weight-record".as_ref(),
+                        &voter_authority.to_bytes(),
+                    ],
+                    &self.program_id,
+                );
+                VoterCookie {
+                    address: voter,
+                    authority: *voter_authority,
+                    voter_weight_record,
+                    token_owner_record: Pubkey::new_unique(), // don't have it
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::GrantBatch {
+                kind: lockup_kind,
+                start_ts,
+                periods,
+                allow_clawback,
+                grants: grants
+                    .iter()
+                    .map(
+                        |&(voter_authority, amount)| voter_stake_registry::GrantBatchItem {
+                            voter_authority,
+                            amount,
+                        },
+                    )
+                    .collect(),
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::GrantBatch {
+                registrar: registrar.address,
+                deposit_token,
+                token_authority: token_authority.pubkey(),
+                grant_authority: grant_authority.pubkey(),
+                payer: token_authority.pubkey(),
+                deposit_mint: voting_mint.mint.pubkey.unwrap(),
+                system_program: solana_sdk::system_program::id(),
+                token_program: spl_token::id(),
+                associated_token_program: spl_associated_token_account::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+        for voter_cookie in voter_cookies.iter() {
+            for address in [
+                voter_cookie.address,
+                voter_cookie.voter_weight_record,
+                voter_cookie.vault_address(&voting_mint),
+            ] {
+                accounts.push(anchor_lang::prelude::AccountMeta::new(address, false));
+            }
+        }
+        for voter_cookie in voter_cookies.iter() {
+            accounts.push(anchor_lang::prelude::AccountMeta::new(
+                self.voter_weight_checkpoints_address(voter_cookie),
+                false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&grant_authority.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&token_authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
+            .await?;
+
+        Ok(voter_cookies)
+    }
+
+    #[allow(dead_code)]
+    pub async fn clawback(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: Option<u64>,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Clawback {
+                deposit_entry_index,
+                amount,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::Clawback {
+                registrar: registrar.address,
+                voter: voter.address,
+                vault,
+                destination: token_address,
+                authority: authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        // only needed for ClawbackPolicy::RequireNoOutstandingVotes
+        accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+            voter.token_owner_record,
+            false,
+        ));
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+        // only needed if records don't expire in the current slot
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            voter.voter_weight_record,
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn fund_grant(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        deposit_token: Pubkey,
+        token_authority: &Keypair,
+        grant_authority: &Keypair,
+        deposit_entry_index: u8,
+        amount: u64,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::FundGrant {
+                deposit_entry_index,
+                amount,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::FundGrant {
+                registrar: registrar.address,
+                voter: voter.address,
+                vault,
+                deposit_token,
+                token_authority: token_authority.pubkey(),
+                grant_authority: grant_authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&grant_authority.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&token_authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn revoke_grant(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        grantor: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::RevokeGrant {
+                deposit_entry_index,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::RevokeGrant {
+                registrar: registrar.address,
+                grantor: grantor.pubkey(),
+                voter: voter.address,
+                token_owner_record: voter.token_owner_record,
+                vault,
+                destination: token_address,
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+        // only needed if records don't expire in the current slot
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            voter.voter_weight_record,
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&grantor.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_clawback_policy(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        policy: voter_stake_registry::state::ClawbackPolicy,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureClawbackPolicy { policy },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureClawbackPolicy {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_voter_weight_expiry(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        expiry: voter_stake_registry::state::VoterWeightExpiry,
+        slots: u64,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureVoterWeightExpiry { expiry, slots },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureVoterWeightExpiry {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_voter_weight_record_version(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        version: voter_stake_registry::state::VoterWeightRecordVersion,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureVoterWeightRecordVersion { version },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureVoterWeightRecordVersion {
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn withdraw(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+    ) -> std::result::Result<(), TransportError> {
+        self.withdraw_impl(
+            registrar,
+            voter,
+            voting_mint,
+            authority,
+            token_address,
+            deposit_entry_index,
+            amount,
+            &[],
+        )
+        .await
+    }
+
+    /// Withdraw for a voter that delegates to the owner of `delegate_token_owner_record`
+    #[allow(dead_code)]
+    pub async fn withdraw_with_delegate(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+        delegate_token_owner_record: Pubkey,
+    ) -> std::result::Result<(), TransportError> {
+        self.withdraw_impl(
+            registrar,
+            voter,
+            voting_mint,
+            authority,
+            token_address,
+            deposit_entry_index,
+            amount,
+            &[delegate_token_owner_record],
+        )
+        .await
+    }
+
+    /// Withdraw from a deposit entry that is delegated to `delegate`
+    #[allow(dead_code)]
+    pub async fn withdraw_with_entry_delegate(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+        delegate: &VoterCookie,
+    ) -> std::result::Result<(), TransportError> {
+        self.withdraw_impl(
+            registrar,
+            voter,
+            voting_mint,
+            authority,
+            token_address,
+            deposit_entry_index,
+            amount,
+            &[delegate.address, delegate.token_owner_record],
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    async fn withdraw_impl(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+        delegate_accounts: &[Pubkey],
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Withdraw {
+                deposit_entry_index,
+                amount,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::Withdraw {
+                registrar: registrar.address,
+                voter: voter.address,
+                token_owner_record: voter.token_owner_record,
+                voter_weight_record: voter.voter_weight_record,
+                vault,
+                destination: token_address,
+                voter_authority: authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+        for delegate_account in delegate_accounts {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *delegate_account,
+                false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_clawback_authority(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        index: u16,
+        voting_mint: &VotingMintConfigCookie,
+        clawback_authority: Option<Pubkey>,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureClawbackAuthority {
+                idx: index,
+                clawback_authority,
+            },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureClawbackAuthority {
+                mint: voting_mint.mint.pubkey.unwrap(),
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_clawback_destination(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        index: u16,
+        voting_mint: &VotingMintConfigCookie,
+        clawback_destination: Option<Pubkey>,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureClawbackDestination {
+                idx: index,
+                clawback_destination,
+            },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureClawbackDestination {
+                mint: voting_mint.mint.pubkey.unwrap(),
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn configure_unlock_early(
+        &self,
+        registrar: &RegistrarCookie,
+        authority: &Keypair,
+        index: u16,
+        voting_mint: &VotingMintConfigCookie,
+        penalty_destination: Option<Pubkey>,
+        max_penalty_scaled_factor: f64,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureUnlockEarly {
+                idx: index,
+                penalty_destination,
+                max_penalty_scaled_factor: (max_penalty_scaled_factor * 1e9) as u64,
+            },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ConfigureUnlockEarly {
+                mint: voting_mint.mint.pubkey.unwrap(),
+                registrar: registrar.address,
+                realm_authority: authority.pubkey(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn unlock_early(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        authority: &Keypair,
+        token_address: Pubkey,
+        penalty_destination: Pubkey,
+        deposit_entry_index: u8,
+        amount: u64,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::UnlockEarly {
+                deposit_entry_index,
+                amount,
+            });
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::UnlockEarly {
+                registrar: registrar.address,
+                voter: voter.address,
+                token_owner_record: voter.token_owner_record,
+                voter_weight_record: voter.voter_weight_record,
+                vault,
+                destination: token_address,
+                penalty_destination,
+                voter_authority: authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn close_voter(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        voting_mint: &VotingMintConfigCookie,
+        voter_authority: &Keypair,
+    ) -> std::result::Result<(), TransportError> {
+        let vault = voter.vault_address(&voting_mint);
+
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::CloseVoter {});
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CloseVoter {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_authority: voter_authority.pubkey(),
+                sol_destination: voter_authority.pubkey(),
+                token_program: spl_token::id(),
+            },
+            None,
+        );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(vault, false));
+        let voter_data = self
+            .solana
+            .get_account::<voter_stake_registry::state::Voter>(voter.address)
+            .await;
+        if voter_data.has_voter_weight_checkpoints {
+            accounts.push(anchor_lang::prelude::AccountMeta::new(
+                self.voter_weight_checkpoints_address(voter),
+                false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&voter_authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    // records need an action, CreateProposal is the common one in tests
+    pub fn update_voter_weight_record_instruction(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+    ) -> Instruction {
+        self.update_voter_weight_record_for_action_instruction(
+            registrar,
+            voter,
+            Some(VoterWeightAction::CreateProposal),
+            None,
+        )
+    }
+
+    #[allow(dead_code)]
+    pub fn update_voter_weight_record_for_action_instruction(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        weight_action: Option<VoterWeightAction>,
+        weight_action_target: Option<Pubkey>,
+    ) -> Instruction {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::UpdateVoterWeightRecord {
+                weight_action,
+                weight_action_target,
+            },
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::UpdateVoterWeightRecord {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_weight_record: voter.voter_weight_record,
+                system_program: solana_sdk::system_program::id(),
+            },
+            None,
+        );
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn update_voter_weight_record_for_vote_instruction(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        proposal: &ProposalCookie,
+    ) -> Instruction {
+        let mut instruction = self.update_voter_weight_record_for_action_instruction(
+            registrar,
+            voter,
+            Some(VoterWeightAction::CastVote),
+            Some(proposal.address),
+        );
+        instruction.accounts.extend([
+            anchor_lang::prelude::AccountMeta::new_readonly(
+                self.voter_weight_snapshot_address(voter, proposal.address),
+                false,
+            ),
+            anchor_lang::prelude::AccountMeta::new_readonly(proposal.governance, false),
+            anchor_lang::prelude::AccountMeta::new_readonly(proposal.address, false),
+            anchor_lang::prelude::AccountMeta::new_readonly(voter.authority, true),
+        ]);
+        instruction
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_voter_weight_record(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+    ) -> std::result::Result<voter_stake_registry::state::VoterWeightRecord, TransportError> {
+        self.update_voter_weight_record_for_action(
+            registrar,
+            voter,
+            Some(VoterWeightAction::CreateProposal),
+            None,
+        )
+        .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_voter_weight_record_for_action(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        weight_action: Option<VoterWeightAction>,
+        weight_action_target: Option<Pubkey>,
+    ) -> std::result::Result<voter_stake_registry::state::VoterWeightRecord, TransportError> {
+        let instructions = vec![self.update_voter_weight_record_for_action_instruction(
+            registrar,
+            voter,
+            weight_action,
+            weight_action_target,
+        )];
+
+        self.solana.process_transaction(&instructions, None).await?;
+
+        Ok(self
+            .solana
+            .get_account::<voter_stake_registry::state::VoterWeightRecord>(
+                voter.voter_weight_record,
+            )
+            .await)
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_voter_weight_record_for_vote(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        authority: &Keypair,
+        proposal: &ProposalCookie,
+    ) -> std::result::Result<voter_stake_registry::state::VoterWeightRecord, TransportError> {
+        let instructions =
+            vec![self.update_voter_weight_record_for_vote_instruction(registrar, voter, proposal)];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await?;
+
+        Ok(self
+            .solana
+            .get_account::<voter_stake_registry::state::VoterWeightRecord>(
+                voter.voter_weight_record,
+            )
+            .await)
+    }
+
+    #[allow(dead_code)]
+    pub fn voter_weight_checkpoints_address(&self, voter: &VoterCookie) -> Pubkey {
+        Pubkey::find_program_address(
+            &[voter.address.as_ref(), b"voter-weight-checkpoints".as_ref()],
+            &self.program_id,
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_voter_weight_checkpoints(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        payer: &Keypair,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::CreateVoterWeightCheckpoints {},
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CreateVoterWeightCheckpoints {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_weight_checkpoints: self.voter_weight_checkpoints_address(voter),
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&payer.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
+    }
+
+    #[allow(dead_code)]
+    pub async fn get_voter_weight_at(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        ts: i64,
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::GetVoterWeightAt { ts },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::GetVoterWeightAt {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_weight_checkpoints: self.voter_weight_checkpoints_address(voter),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana.process_transaction(&instructions, None).await
+    }
+
+    #[allow(dead_code)]
+    pub fn voter_weight_snapshot_address(&self, voter: &VoterCookie, proposal: Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                voter.address.as_ref(),
+                b"voter-weight-snapshot".as_ref(),
+                proposal.as_ref(),
+            ],
+            &self.program_id,
+        )
+        .0
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_voter_weight_snapshot(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        governance: Pubkey,
+        proposal: Pubkey,
+        payer: &Keypair,
+    ) -> std::result::Result<voter_stake_registry::state::VoterWeightSnapshot, TransportError> {
+        let voter_weight_snapshot = self.voter_weight_snapshot_address(voter, proposal);
+
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::CreateVoterWeightSnapshot {},
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CreateVoterWeightSnapshot {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_weight_checkpoints: self.voter_weight_checkpoints_address(voter),
+                governance,
+                proposal,
+                voter_weight_snapshot,
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&payer.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await?;
+
+        Ok(self
+            .solana
+            .get_account::<voter_stake_registry::state::VoterWeightSnapshot>(voter_weight_snapshot)
+            .await)
+    }
+
+    #[allow(dead_code)]
+    pub fn update_voter_weight_record_with_delegations_instruction(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        delegators: &[&VoterCookie],
+        weight_action: Option<VoterWeightAction>,
+        proposal: Option<&ProposalCookie>,
+    ) -> Instruction {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::UpdateVoterWeightRecordWithDelegations {
+                weight_action,
+                weight_action_target: proposal.map(|p| p.address),
+            },
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::UpdateVoterWeightRecord {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_weight_record: voter.voter_weight_record,
+                system_program: solana_sdk::system_program::id(),
+            },
+            None,
+        );
+        if let Some(proposal) = proposal {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                self.voter_weight_snapshot_address(voter, proposal.address),
+                false,
+            ));
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                proposal.governance,
+                false,
+            ));
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                proposal.address,
+                false,
+            ));
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                voter.authority,
+                true,
+            ));
+        }
+        for delegator in delegators {
+            accounts.push(anchor_lang::prelude::AccountMeta::new(
+                delegator.address,
+                false,
+            ));
+        }
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_voter_weight_record_with_delegations(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        authority: &Keypair,
+        delegators: &[&VoterCookie],
+        weight_action: Option<VoterWeightAction>,
+        proposal: Option<&ProposalCookie>,
+    ) -> std::result::Result<voter_stake_registry::state::VoterWeightRecord, TransportError> {
+        let instructions = vec![
+            self.update_voter_weight_record_with_delegations_instruction(
+                registrar,
+                voter,
+                delegators,
+                weight_action,
+                proposal,
+            ),
+        ];
+
+        // clone the secrets, the voter authority only signs for votes
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+        let signers: &[&Keypair] = if proposal.is_some() { &[&signer] } else { &[] };
+
+        self.solana
+            .process_transaction(&instructions, Some(signers))
+            .await?;
+
+        Ok(self
+            .solana
+            .get_account::<voter_stake_registry::state::VoterWeightRecord>(
+                voter.voter_weight_record,
+            )
+            .await)
+    }
+
+    #[allow(dead_code)]
+    pub async fn release_vote_locks(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        vote_records: &[Pubkey],
+    ) -> std::result::Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ReleaseVoteLocks {},
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::ReleaseVoteLocks {
+                registrar: registrar.address,
+                voter: voter.address,
+            },
+            None,
+        );
+        for vote_record in vote_records {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *vote_record,
+                false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana.process_transaction(&instructions, None).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn update_voter_weight_records(
+        &self,
+        registrar: &RegistrarCookie,
+        voters_and_records: &[(Pubkey, Pubkey)],
+    ) -> std::result::Result<Vec<voter_stake_registry::events::VoterWeightChanged>, TransportError>
+    {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::UpdateVoterWeightRecords {},
+        );
+
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::UpdateVoterWeightRecords {
+                registrar: registrar.address,
+            },
+            None,
+        );
+        for (voter, voter_weight_record) in voters_and_records {
+            accounts.push(anchor_lang::prelude::AccountMeta::new_readonly(
+                *voter, false,
+            ));
+            accounts.push(anchor_lang::prelude::AccountMeta::new(
+                *voter_weight_record,
+                false,
+            ));
+        }
+
+        let instructions = vec![Instruction {
+            program_id: self.program_id,
+            accounts,
+            data,
+        }];
+
+        self.solana.process_transaction(&instructions, None).await?;
+
+        Ok(self
+            .solana
+            .program_output()
+            .data
+            .iter()
+            .filter_map(|data| {
+                deserialize_event::<voter_stake_registry::events::VoterWeightChanged>(data)
+            })
+            .collect())
+    }
+
+    #[allow(dead_code)]
+    pub async fn create_proposal(
+        &self,
+        registrar: &RegistrarCookie,
+        realm: &GovernanceRealmCookie,
+        governance: Pubkey,
+        voter: &VoterCookie,
+        authority: &Keypair,
+        payer: &Keypair,
+    ) -> std::result::Result<ProposalCookie, TransportError> {
+        let governance_program_id = realm.governance.program_id;
+        let governing_token_mint = realm.community_token_mint.pubkey.unwrap();
+        let proposal = spl_governance::state::proposal::get_proposal_address(
+            &governance_program_id,
+            &governance,
+            &governing_token_mint,
+            &0u32.to_le_bytes(),
+        );
+
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::CreateProposal {
+                name: "test proposal".into(),
+                description_link: "description".into(),
+                vote_type: spl_governance::state::proposal::VoteType::SingleChoice,
+                options: vec!["yes".into()],
+                use_deny_option: true,
+                proposal_index: 0,
+            },
+        );
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CreateProposal {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_authority: authority.pubkey(),
+                voter_weight_record: voter.voter_weight_record,
+                governance_program_id,
+                realm: realm.realm,
+                realm_config: realm.realm_config,
+                governance,
+                proposal,
+                proposal_owner_record: voter.token_owner_record,
+                governing_token_mint,
+                payer: payer.pubkey(),
+                system_program: solana_sdk::system_program::id(),
+                rent: solana_program::sysvar::rent::id(),
+            },
+            None,
+        );
+
+        // also sign off, so the proposal can be voted on
+        let instructions = vec![
+            Instruction {
+                program_id: self.program_id,
+                accounts,
+                data,
+            },
+            spl_governance::instruction::add_signatory(
+                &governance_program_id,
+                &proposal,
+                &voter.token_owner_record,
+                &authority.pubkey(),
+                &payer.pubkey(),
+                &authority.pubkey(),
+            ),
+            spl_governance::instruction::sign_off_proposal(
+                &governance_program_id,
+                &realm.realm,
+                &governance,
+                &proposal,
+                &authority.pubkey(),
+                None,
+            ),
+        ];
+
+        // clone the secrets
+        let signer1 = Keypair::from_base58_string(&payer.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&authority.to_base58_string());
+
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
+            .await?;
+
+        Ok(ProposalCookie {
+            address: proposal,
+            governance,
+            owner_token_owner_record: voter.token_owner_record,
+        })
+    }
+
+    #[allow(dead_code)]
+    pub async fn cast_vote(
+        &self,
+        registrar: &RegistrarCookie,
+        realm: &GovernanceRealmCookie,
+        governance: Pubkey,
+        proposal: &ProposalCookie,
+        voter: &VoterCookie,
+        authority: &Keypair,
+        payer: &Keypair,
+    ) -> std::result::Result<(), TransportError> {
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::CastVote {
+                vote: spl_governance::state::vote_record::Vote::Approve(vec![
+                    spl_governance::state::vote_record::VoteChoice {
+                        rank: 0,
+                        weight_percentage: 100,
+                    },
+                ]),
+            });
+
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CastVote {
+                registrar: registrar.address,
+                voter: voter.address,
+                voter_authority: authority.pubkey(),
+                voter_weight_record: voter.voter_weight_record,
+                voter_weight_snapshot: self.voter_weight_snapshot_address(voter, proposal.address),
+                governance_program_id: realm.governance.program_id,
+                realm: realm.realm,
+                realm_config: realm.realm_config,
+                governance,
+                proposal: proposal.address,
+                proposal_owner_record: proposal.owner_token_owner_record,
+                voter_token_owner_record: voter.token_owner_record,
+                governing_token_mint: realm.community_token_mint.pubkey.unwrap(),
+                vote_record: realm.vote_record_address(proposal, voter.token_owner_record),
+                payer: payer.pubkey(),
                 system_program: solana_sdk::system_program::id(),
-                token_program: spl_token::id(),
-                associated_token_program: spl_associated_token_account::id(),
                 rent: solana_program::sysvar::rent::id(),
             },
             None,
@@ -270,40 +2148,41 @@ impl AddinCookie {
         }];
 
         // clone the secrets
-        let signer1 = Keypair::from_base58_string(&voter_authority.to_base58_string());
+        let signer1 = Keypair::from_base58_string(&payer.to_base58_string());
+        let signer2 = Keypair::from_base58_string(&authority.to_base58_string());
 
         self.solana
-            .process_transaction(&instructions, Some(&[&signer1]))
+            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
             .await
     }
 
     #[allow(dead_code)]
-    pub async fn deposit(
+    pub async fn relinquish_vote(
         &self,
         registrar: &RegistrarCookie,
+        realm: &GovernanceRealmCookie,
+        governance: Pubkey,
+        proposal: &ProposalCookie,
         voter: &VoterCookie,
-        voting_mint: &VotingMintConfigCookie,
         authority: &Keypair,
-        token_address: Pubkey,
-        deposit_entry_index: u8,
-        amount: u64,
+        beneficiary: Pubkey,
     ) -> std::result::Result<(), TransportError> {
-        let vault = voter.vault_address(&voting_mint);
-
-        let data =
-            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Deposit {
-                deposit_entry_index,
-                amount,
-            });
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::RelinquishVote {},
+        );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::Deposit {
+            &voter_stake_registry::accounts::RelinquishVote {
                 registrar: registrar.address,
                 voter: voter.address,
-                vault: vault,
-                deposit_token: token_address,
-                deposit_authority: authority.pubkey(),
-                token_program: spl_token::id(),
+                voter_authority: authority.pubkey(),
+                governance_program_id: realm.governance.program_id,
+                voter_token_owner_record: voter.token_owner_record,
+                governance,
+                proposal: proposal.address,
+                governing_token_mint: realm.community_token_mint.pubkey.unwrap(),
+                vote_record: realm.vote_record_address(proposal, voter.token_owner_record),
+                beneficiary,
             },
             None,
         );
@@ -323,70 +2202,24 @@ impl AddinCookie {
     }
 
     #[allow(dead_code)]
-    pub async fn grant(
+    pub async fn set_delegate(
         &self,
         registrar: &RegistrarCookie,
-        voter_authority: Pubkey,
-        voting_mint: &VotingMintConfigCookie,
-        lockup_kind: voter_stake_registry::state::LockupKind,
-        start_ts: Option<u64>,
-        periods: u32,
-        allow_clawback: bool,
-        amount: u64,
-        deposit_token: Pubkey,
-        token_authority: &Keypair,
-        grant_authority: &Keypair,
-    ) -> std::result::Result<VoterCookie, TransportError> {
-        let (voter, voter_bump) = Pubkey::find_program_address(
-            &[
-                &registrar.address.to_bytes(),
-                b"voter".as_ref(),
-                &voter_authority.to_bytes(),
-            ],
-            &self.program_id,
-        );
-        let (voter_weight_record, voter_weight_record_bump) = Pubkey::find_program_address(
-            &[
-                &registrar.address.to_bytes(),
-                b"voter-weight-record".as_ref(),
-                &voter_authority.to_bytes(),
-            ],
-            &self.program_id,
-        );
-        let voter_cookie = VoterCookie {
-            address: voter,
-            authority: voter_authority,
-            voter_weight_record,
-            token_owner_record: Pubkey::new_unique(), // don't have it
-        };
-        let vault = voter_cookie.vault_address(&voting_mint);
-
-        let data = anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Grant {
-            voter_bump,
-            voter_weight_record_bump,
-            kind: lockup_kind,
-            start_ts,
-            periods,
-            allow_clawback,
-            amount,
-        });
+        voter: &VoterCookie,
+        authority: &Keypair,
+        delegate: Pubkey,
+    ) -> std::result::Result<(), TransportError> {
+        let data =
+            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::SetDelegate {
+                delegate,
+            });
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::Grant {
+            &voter_stake_registry::accounts::SetDelegate {
                 registrar: registrar.address,
-                voter,
-                voter_authority,
-                voter_weight_record,
-                vault,
-                deposit_token,
-                token_authority: token_authority.pubkey(),
-                grant_authority: grant_authority.pubkey(),
-                payer: token_authority.pubkey(),
-                deposit_mint: voting_mint.mint.pubkey.unwrap(),
-                system_program: solana_sdk::system_program::id(),
-                token_program: spl_token::id(),
-                associated_token_program: spl_associated_token_account::id(),
-                rent: solana_program::sysvar::rent::id(),
+                voter: voter.address,
+                voter_authority: authority.pubkey(),
+                voter_weight_record: voter.voter_weight_record,
             },
             None,
         );
@@ -398,41 +2231,35 @@ impl AddinCookie {
         }];
 
         // clone the secrets
-        let signer1 = Keypair::from_base58_string(&grant_authority.to_base58_string());
-        let signer2 = Keypair::from_base58_string(&token_authority.to_base58_string());
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
 
         self.solana
-            .process_transaction(&instructions, Some(&[&signer1, &signer2]))
-            .await?;
-
-        Ok(voter_cookie)
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
     }
 
     #[allow(dead_code)]
-    pub async fn clawback(
+    pub async fn set_deposit_entry_delegate(
         &self,
         registrar: &RegistrarCookie,
         voter: &VoterCookie,
-        voting_mint: &VotingMintConfigCookie,
-        realm_authority: &Keypair,
-        token_address: Pubkey,
+        authority: &Keypair,
         deposit_entry_index: u8,
+        delegate: Pubkey,
     ) -> std::result::Result<(), TransportError> {
-        let vault = voter.vault_address(&voting_mint);
-
-        let data =
-            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Clawback {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::SetDepositEntryDelegate {
                 deposit_entry_index,
-            });
+                delegate,
+            },
+        );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::Clawback {
+            &voter_stake_registry::accounts::SetDepositEntryDelegate {
                 registrar: registrar.address,
                 voter: voter.address,
-                vault,
-                destination: token_address,
-                realm_authority: realm_authority.pubkey(),
-                token_program: spl_token::id(),
+                voter_authority: authority.pubkey(),
+                voter_weight_record: voter.voter_weight_record,
             },
             None,
         );
@@ -444,7 +2271,7 @@ impl AddinCookie {
         }];
 
         // clone the secrets
-        let signer = Keypair::from_base58_string(&realm_authority.to_base58_string());
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
 
         self.solana
             .process_transaction(&instructions, Some(&[&signer]))
@@ -452,34 +2279,24 @@ impl AddinCookie {
     }
 
     #[allow(dead_code)]
-    pub async fn withdraw(
+    pub async fn configure_voter_weight_action(
         &self,
         registrar: &RegistrarCookie,
-        voter: &VoterCookie,
-        voting_mint: &VotingMintConfigCookie,
         authority: &Keypair,
-        token_address: Pubkey,
-        deposit_entry_index: u8,
-        amount: u64,
+        action: VoterWeightAction,
+        min_lockup_secs: u64,
     ) -> std::result::Result<(), TransportError> {
-        let vault = voter.vault_address(&voting_mint);
-
-        let data =
-            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::Withdraw {
-                deposit_entry_index,
-                amount,
-            });
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::ConfigureVoterWeightAction {
+                action,
+                min_lockup_secs,
+            },
+        );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::Withdraw {
+            &voter_stake_registry::accounts::ConfigureVoterWeightAction {
                 registrar: registrar.address,
-                voter: voter.address,
-                token_owner_record: voter.token_owner_record,
-                voter_weight_record: voter.voter_weight_record,
-                vault,
-                destination: token_address,
-                voter_authority: authority.pubkey(),
-                token_program: spl_token::id(),
+                realm_authority: authority.pubkey(),
             },
             None,
         );
@@ -499,29 +2316,25 @@ impl AddinCookie {
     }
 
     #[allow(dead_code)]
-    pub async fn close_voter(
+    pub async fn close_deposit_entry(
         &self,
-        registrar: &RegistrarCookie,
         voter: &VoterCookie,
-        voting_mint: &VotingMintConfigCookie,
-        voter_authority: &Keypair,
-    ) -> std::result::Result<(), TransportError> {
-        let vault = voter.vault_address(&voting_mint);
-
-        let data =
-            anchor_lang::InstructionData::data(&voter_stake_registry::instruction::CloseVoter {});
+        authority: &Keypair,
+        deposit_entry_index: u8,
+    ) -> Result<(), TransportError> {
+        let data = anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::CloseDepositEntry {
+                deposit_entry_index,
+            },
+        );
 
-        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::CloseVoter {
-                registrar: registrar.address,
+        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::CloseDepositEntry {
                 voter: voter.address,
-                voter_authority: voter_authority.pubkey(),
-                sol_destination: voter_authority.pubkey(),
-                token_program: spl_token::id(),
+                voter_authority: authority.pubkey(),
             },
             None,
         );
-        accounts.push(anchor_lang::prelude::AccountMeta::new(vault, false));
 
         let instructions = vec![Instruction {
             program_id: self.program_id,
@@ -530,74 +2343,69 @@ impl AddinCookie {
         }];
 
         // clone the secrets
-        let signer = Keypair::from_base58_string(&voter_authority.to_base58_string());
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
 
         self.solana
             .process_transaction(&instructions, Some(&[&signer]))
             .await
     }
 
-    pub fn update_voter_weight_record_instruction(
+    #[allow(dead_code)]
+    pub async fn set_lock_delegate(
         &self,
         registrar: &RegistrarCookie,
         voter: &VoterCookie,
-    ) -> Instruction {
+        authority: &Keypair,
+        deposit_entry_index: u8,
+        lock_delegate: Pubkey,
+    ) -> Result<(), TransportError> {
         let data = anchor_lang::InstructionData::data(
-            &voter_stake_registry::instruction::UpdateVoterWeightRecord {},
+            &voter_stake_registry::instruction::SetLockDelegate {
+                deposit_entry_index,
+                lock_delegate,
+            },
         );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::UpdateVoterWeightRecord {
+            &voter_stake_registry::accounts::SetLockDelegate {
                 registrar: registrar.address,
                 voter: voter.address,
-                voter_weight_record: voter.voter_weight_record,
-                system_program: solana_sdk::system_program::id(),
+                voter_authority: authority.pubkey(),
             },
             None,
         );
 
-        Instruction {
+        let instructions = vec![Instruction {
             program_id: self.program_id,
             accounts,
             data,
-        }
-    }
-
-    #[allow(dead_code)]
-    pub async fn update_voter_weight_record(
-        &self,
-        registrar: &RegistrarCookie,
-        voter: &VoterCookie,
-    ) -> std::result::Result<voter_stake_registry::state::VoterWeightRecord, TransportError> {
-        let instructions = vec![self.update_voter_weight_record_instruction(registrar, voter)];
+        }];
 
-        self.solana.process_transaction(&instructions, None).await?;
+        // clone the secrets
+        let signer = Keypair::from_base58_string(&authority.to_base58_string());
 
-        Ok(self
-            .solana
-            .get_account::<voter_stake_registry::state::VoterWeightRecord>(
-                voter.voter_weight_record,
-            )
-            .await)
+        self.solana
+            .process_transaction(&instructions, Some(&[&signer]))
+            .await
     }
 
     #[allow(dead_code)]
-    pub async fn close_deposit_entry(
+    pub async fn release_lock_delegate(
         &self,
         voter: &VoterCookie,
-        authority: &Keypair,
+        lock_delegate: &Keypair,
         deposit_entry_index: u8,
     ) -> Result<(), TransportError> {
         let data = anchor_lang::InstructionData::data(
-            &voter_stake_registry::instruction::CloseDepositEntry {
+            &voter_stake_registry::instruction::ReleaseLockDelegate {
                 deposit_entry_index,
             },
         );
 
         let accounts = anchor_lang::ToAccountMetas::to_account_metas(
-            &voter_stake_registry::accounts::CloseDepositEntry {
+            &voter_stake_registry::accounts::ReleaseLockDelegate {
                 voter: voter.address,
-                voter_authority: authority.pubkey(),
+                lock_delegate: lock_delegate.pubkey(),
             },
             None,
         );
@@ -609,7 +2417,7 @@ impl AddinCookie {
         }];
 
         // clone the secrets
-        let signer = Keypair::from_base58_string(&authority.to_base58_string());
+        let signer = Keypair::from_base58_string(&lock_delegate.to_base58_string());
 
         self.solana
             .process_transaction(&instructions, Some(&[&signer]))
@@ -633,7 +2441,7 @@ impl AddinCookie {
                 periods,
             });
 
-        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
             &voter_stake_registry::accounts::ResetLockup {
                 registrar: registrar.address,
                 voter: voter.address,
@@ -641,6 +2449,10 @@ impl AddinCookie {
             },
             None,
         );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
 
         let instructions = vec![Instruction {
             program_id: self.program_id,
@@ -674,7 +2486,7 @@ impl AddinCookie {
             },
         );
 
-        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
             &voter_stake_registry::accounts::InternalTransferLocked {
                 registrar: registrar.address,
                 voter: voter.address,
@@ -682,6 +2494,15 @@ impl AddinCookie {
             },
             None,
         );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+        // only needed if records don't expire in the current slot
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            voter.voter_weight_record,
+            false,
+        ));
 
         let instructions = vec![Instruction {
             program_id: self.program_id,
@@ -715,7 +2536,7 @@ impl AddinCookie {
             },
         );
 
-        let accounts = anchor_lang::ToAccountMetas::to_account_metas(
+        let mut accounts = anchor_lang::ToAccountMetas::to_account_metas(
             &voter_stake_registry::accounts::InternalTransferUnlocked {
                 registrar: registrar.address,
                 voter: voter.address,
@@ -723,6 +2544,15 @@ impl AddinCookie {
             },
             None,
         );
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            self.voter_weight_checkpoints_address(voter),
+            false,
+        ));
+        // only needed if records don't expire in the current slot
+        accounts.push(anchor_lang::prelude::AccountMeta::new(
+            voter.voter_weight_record,
+            false,
+        ));
 
         let instructions = vec![Instruction {
             program_id: self.program_id,
@@ -771,6 +2601,31 @@ impl AddinCookie {
             .unwrap();
     }
 
+    /// Queries get_voter_weights through CPI from the cpi_tester program
+    #[allow(dead_code)]
+    pub async fn get_voter_weights_via_cpi(
+        &self,
+        registrar: &RegistrarCookie,
+        voter: &VoterCookie,
+        at_ts: i64,
+    ) -> Result<voter_stake_registry::VoterWeights, TransportError> {
+        let instructions = vec![Instruction {
+            program_id: cpi_tester::id(),
+            accounts: vec![
+                anchor_lang::prelude::AccountMeta::new_readonly(registrar.address, false),
+                anchor_lang::prelude::AccountMeta::new_readonly(voter.address, false),
+                anchor_lang::prelude::AccountMeta::new_readonly(self.program_id, false),
+            ],
+            data: at_ts.to_le_bytes().to_vec(),
+        }];
+
+        self.solana.process_transaction(&instructions, None).await?;
+
+        let data_log = self.solana.program_output().data;
+        let return_data = base64::decode(data_log.last().unwrap()).unwrap();
+        Ok(anchor_lang::AnchorDeserialize::try_from_slice(&return_data).unwrap())
+    }
+
     #[allow(dead_code)]
     pub async fn set_time_offset(
         &self,
@@ -807,6 +2662,17 @@ impl AddinCookie {
     }
 }
 
+impl RegistrarCookie {
+    #[allow(dead_code)]
+    pub fn supply_exclusions_address(&self, program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[&self.address.to_bytes(), b"supply-exclusions".as_ref()],
+            program_id,
+        )
+        .0
+    }
+}
+
 impl VotingMintConfigCookie {
     #[allow(dead_code)]
     pub async fn vault_balance(&self, solana: &SolanaCookie, voter: &VoterCookie) -> u64 {
@@ -0,0 +1,59 @@
+//! A minimal program that queries voter-stake-registry through CPI, like an
+//! external program using the `cpi` feature would.
+
+use std::convert::TryInto;
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    log::sol_log_data,
+    program::{get_return_data, invoke},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+solana_program::declare_id!("VsrCpiTester1111111111111111111111111111111");
+
+/// Calls get_voter_weights and logs the returned data.
+///
+/// Accounts: registrar, voter, voter-stake-registry program.
+/// Data: `at_ts` as little endian i64.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (registrar, voter) = match accounts {
+        [registrar, voter, _program] => (registrar, voter),
+        _ => return Err(ProgramError::NotEnoughAccountKeys),
+    };
+    let at_ts = i64::from_le_bytes(
+        data.try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?,
+    );
+
+    let instruction = Instruction {
+        program_id: voter_stake_registry::id(),
+        accounts: anchor_lang::ToAccountMetas::to_account_metas(
+            &voter_stake_registry::accounts::GetVoterWeights {
+                registrar: *registrar.key,
+                voter: *voter.key,
+            },
+            None,
+        ),
+        data: anchor_lang::InstructionData::data(
+            &voter_stake_registry::instruction::GetVoterWeights { at_ts },
+        ),
+    };
+    invoke(&instruction, accounts)?;
+
+    let (return_program_id, return_data) =
+        get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+    if return_program_id != voter_stake_registry::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    sol_log_data(&[&return_data]);
+
+    Ok(())
+}
@@ -41,6 +41,7 @@ pub struct MintGovernanceCookie {
 #[derive(Debug)]
 pub struct ProposalCookie {
     pub address: Pubkey,
+    pub governance: Pubkey,
     pub owner_token_owner_record: Pubkey,
 }
 
@@ -312,6 +313,7 @@ impl GovernanceRealmCookie {
 
         Ok(ProposalCookie {
             address: proposal,
+            governance,
             owner_token_owner_record: voter.token_owner_record,
         })
     }
@@ -382,4 +384,65 @@ impl GovernanceRealmCookie {
             .process_transaction(&instructions, Some(&[&signer]))
             .await
     }
+
+    #[allow(dead_code)]
+    pub fn vote_record_address(
+        &self,
+        proposal: &ProposalCookie,
+        token_owner_record: Pubkey,
+    ) -> Pubkey {
+        vote_record::get_vote_record_address(
+            &self.governance.program_id,
+            &proposal.address,
+            &token_owner_record,
+        )
+    }
+
+    /// Relinquishes a vote without the voter's signature.
+    ///
+    /// spl-governance only allows that once voting on the proposal has ended,
+    /// so this moves the clock past the max voting time of the governance and
+    /// finalizes the vote first.
+    #[allow(dead_code)]
+    pub async fn force_relinquish_vote(
+        &self,
+        governance: Pubkey,
+        proposal: &ProposalCookie,
+        token_owner_record: Pubkey,
+    ) -> std::result::Result<(), TransportError> {
+        let governance_data = self.governance.solana.get_account_data(governance).await;
+        let mut data_slice: &[u8] = &governance_data;
+        let governance_state: spl_governance::state::governance::GovernanceV2 =
+            anchor_lang::AnchorDeserialize::deserialize(&mut data_slice).unwrap();
+        self.governance
+            .solana
+            .advance_clock_by_secs(governance_state.config.max_voting_time as i64 + 1)
+            .await;
+
+        let instructions = vec![
+            spl_governance::instruction::finalize_vote(
+                &self.governance.program_id,
+                &self.realm,
+                &governance,
+                &proposal.address,
+                &proposal.owner_token_owner_record,
+                &self.community_token_mint.pubkey.unwrap(),
+                None,
+            ),
+            spl_governance::instruction::relinquish_vote(
+                &self.governance.program_id,
+                &governance,
+                &proposal.address,
+                &token_owner_record,
+                &self.community_token_mint.pubkey.unwrap(),
+                None,
+                None,
+            ),
+        ];
+
+        self.governance
+            .solana
+            .process_transaction(&instructions, None)
+            .await
+    }
 }
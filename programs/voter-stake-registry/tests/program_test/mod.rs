@@ -18,6 +18,7 @@ pub use utils::*;
 
 pub mod addin;
 pub mod cookies;
+pub mod cpi_tester;
 pub mod governance;
 pub mod solana;
 pub mod utils;
@@ -115,6 +116,9 @@ impl TestContext {
         // intentionally set to half the limit, to catch potential problems early
         test.set_compute_max_units(120000);
 
+        // spl-governance 2.2.1 already checks the weight_action and weight_action_target
+        // of voter weight records for cast_vote and create_proposal, so the tests run
+        // against the pinned version, see Cargo.toml.
         let governance_program_id =
             Pubkey::from_str(&"GovernanceProgramTest1111111111111111111111").unwrap();
         test.add_program(
@@ -123,6 +127,12 @@ impl TestContext {
             processor!(spl_governance::processor::process_instruction),
         );
 
+        test.add_program(
+            "vsr_cpi_tester",
+            cpi_tester::id(),
+            processor!(cpi_tester::process_instruction),
+        );
+
         // Setup the environment
 
         // Mints
@@ -6,7 +6,7 @@ use anchor_spl::token::TokenAccount;
 use solana_program::{program_pack::Pack, rent::*, system_instruction};
 use solana_program_test::*;
 use solana_sdk::{
-    account::ReadableAccount,
+    account::{AccountSharedData, ReadableAccount},
     instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signer},
@@ -15,6 +15,8 @@ use solana_sdk::{
 };
 use spl_token::*;
 
+use super::MintCookie;
+
 pub struct SolanaCookie {
     pub context: RefCell<ProgramTestContext>,
     pub rent: Rent,
@@ -73,6 +75,13 @@ impl SolanaCookie {
             .unwrap();
     }
 
+    #[allow(dead_code)]
+    pub async fn advance_clock_by_secs(&self, secs: i64) {
+        let mut clock = self.get_clock().await;
+        clock.unix_timestamp += secs;
+        self.context.borrow_mut().set_sysvar(&clock);
+    }
+
     #[allow(dead_code)]
     pub async fn create_token_account(&self, owner: &Pubkey, mint: Pubkey) -> Pubkey {
         let keypair = Keypair::new();
@@ -101,6 +110,41 @@ impl SolanaCookie {
         return keypair.pubkey();
     }
 
+    #[allow(dead_code)]
+    pub async fn mint_to(&self, mint: &MintCookie, destination: Pubkey, amount: u64) {
+        let instructions = [spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey.unwrap(),
+            &destination,
+            &mint.authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap()];
+
+        let signer = Keypair::from_base58_string(&mint.authority.to_base58_string());
+        self.process_transaction(&instructions, Some(&[&signer]))
+            .await
+            .unwrap();
+    }
+
+    #[allow(dead_code)]
+    pub async fn close_token_account(&self, address: Pubkey, owner: &Keypair) {
+        let instructions = [spl_token::instruction::close_account(
+            &spl_token::id(),
+            &address,
+            &owner.pubkey(),
+            &owner.pubkey(),
+            &[],
+        )
+        .unwrap()];
+
+        let signer = Keypair::from_base58_string(&owner.to_base58_string());
+        self.process_transaction(&instructions, Some(&[&signer]))
+            .await
+            .unwrap();
+    }
+
     #[allow(dead_code)]
     pub async fn get_account_data(&self, address: Pubkey) -> Vec<u8> {
         self.context
@@ -114,6 +158,21 @@ impl SolanaCookie {
             .to_vec()
     }
 
+    /// Replaces the data of an existing account, keeping its lamports and owner.
+    #[allow(dead_code)]
+    pub async fn set_account_data(&self, address: Pubkey, data: &[u8]) {
+        let mut context = self.context.borrow_mut();
+        let mut account: AccountSharedData = context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap()
+            .into();
+        account.set_data(data.to_vec());
+        context.set_account(&address, &account);
+    }
+
     #[allow(dead_code)]
     pub async fn get_account<T: AccountDeserialize>(&self, address: Pubkey) -> T {
         let data = self.get_account_data(address).await;
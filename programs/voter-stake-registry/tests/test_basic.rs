@@ -3,7 +3,7 @@ use solana_program_test::*;
 use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
 
 use program_test::*;
-use voter_stake_registry::state::Voter;
+use voter_stake_registry::state::{Voter, VoterWeightCheckpoints};
 
 mod program_test;
 
@@ -189,8 +189,14 @@ async fn test_basic() -> Result<(), TransportError> {
         .solana
         .rent
         .minimum_balance(std::mem::size_of::<Voter>());
+    let checkpoints_rent = context
+        .solana
+        .rent
+        .minimum_balance(8 + std::mem::size_of::<VoterWeightCheckpoints>());
     let tolerance = 60_000;
-    assert!(lamports_after > lamports_before + voter_rent + token_rent - tolerance);
+    assert!(
+        lamports_after > lamports_before + voter_rent + token_rent + checkpoints_rent - tolerance
+    );
 
     Ok(())
 }
@@ -166,6 +166,7 @@ async fn test_clawback() -> Result<(), TransportError> {
             &voter_authority,
             realm_authority_ata,
             0,
+            None,
         )
         .await
         .expect_err("fails because realm_authority is invalid");
@@ -180,6 +181,7 @@ async fn test_clawback() -> Result<(), TransportError> {
             &realm_authority,
             realm_authority_ata,
             0,
+            None,
         )
         .await?;
 
@@ -0,0 +1,169 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_clawback_authority() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+
+    let clawback_authority = &context.users[2].key;
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    // entry 0 is the voter's own deposit, entry 1 a grant
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+    addin
+        .grant(
+            &registrar,
+            voter_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+            12000,
+            grant_funds,
+            &grant_authority,
+            &grant_authority,
+        )
+        .await
+        .unwrap();
+
+    let clawback = |authority, deposit_entry_index| {
+        addin.clawback(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            authority,
+            grant_funds,
+            deposit_entry_index,
+            None,
+        )
+    };
+
+    clawback(clawback_authority, 1)
+        .await
+        .expect_err("no clawback authority configured");
+
+    addin
+        .configure_clawback_authority(
+            &registrar,
+            clawback_authority,
+            0,
+            &mngo_voting_mint,
+            Some(clawback_authority.pubkey()),
+        )
+        .await
+        .expect_err("only the realm authority can configure it");
+    addin
+        .configure_clawback_authority(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(clawback_authority.pubkey()),
+        )
+        .await
+        .unwrap();
+
+    clawback(grant_authority, 1)
+        .await
+        .expect_err("grant authority can't claw back");
+    clawback(clawback_authority, 0)
+        .await
+        .expect_err("clawback authority can only claw back grants");
+
+    // the first clawback attempt with the same arguments failed
+    context.solana.advance_clock_by_slots(2).await;
+    let funds_before = context.solana.token_account_balance(grant_funds).await;
+    clawback(clawback_authority, 1).await.unwrap();
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before + 12000
+    );
+
+    // the realm authority can claw back any entry
+    clawback(&realm_authority, 0).await.unwrap();
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before + 13000
+    );
+    assert_eq!(voter.deposit_amount(&context.solana, 0).await, 0);
+    assert_eq!(voter.deposit_amount(&context.solana, 1).await, 0);
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_clawback_partial() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let community_token_mint = &context.mints[0];
+
+    let realm_authority = &context.users[0].key;
+    let realm_authority_ata = context.users[0].token_accounts[0];
+
+    let voter_authority = &context.users[1].key;
+    let voter_authority_ata = context.users[1].token_accounts[0];
+
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            community_token_mint,
+            &realm_authority,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &realm_authority)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, realm_authority, realm_authority)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            realm_authority,
+            0,
+            community_token_mint,
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+    let treasury = context
+        .solana
+        .create_token_account(
+            &realm_authority.pubkey(),
+            community_token_mint.pubkey.unwrap(),
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(
+            &registrar,
+            &token_owner_record,
+            &voter_authority,
+            &realm_authority,
+        )
+        .await;
+
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            voter_stake_registry::state::LockupKind::Daily,
+            None,
+            10,
+            true,
+        )
+        .await?;
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &realm_authority,
+            realm_authority_ata,
+            0,
+            10000,
+        )
+        .await?;
+
+    let clawback = |destination, amount| {
+        addin.clawback(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &realm_authority,
+            destination,
+            0,
+            amount,
+        )
+    };
+    let withdraw = |amount| {
+        addin.withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_authority_ata,
+            0,
+            amount,
+        )
+    };
+
+    // Advance three days, 3000 tokens are vested
+    let hour = 60 * 60;
+    addin
+        .set_time_offset(&registrar, &realm_authority, (3 * 24 + 1) * hour)
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+
+    clawback(realm_authority_ata, Some(7001))
+        .await
+        .expect_err("only 7000 tokens are locked");
+
+    let realm_ata_before = context
+        .solana
+        .token_account_balance(realm_authority_ata)
+        .await;
+    clawback(realm_authority_ata, Some(3500)).await?;
+    assert_eq!(
+        context
+            .solana
+            .token_account_balance(realm_authority_ata)
+            .await,
+        realm_ata_before + 3500
+    );
+    assert_eq!(voter.deposit_amount(&context.solana, 0).await, 6500);
+
+    // the remaining 3500 locked tokens vest over the remaining 7 days
+    withdraw(3001).await.expect_err("only vested tokens");
+    addin
+        .set_time_offset(&registrar, &realm_authority, (4 * 24 + 1) * hour)
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+    withdraw(3501).await.expect_err("only vested tokens");
+    withdraw(3500).await?;
+
+    // configure the treasury as the only allowed destination
+    addin
+        .configure_clawback_destination(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(treasury),
+        )
+        .await?;
+    clawback(realm_authority_ata, None)
+        .await
+        .expect_err("must claw back to the treasury");
+    clawback(treasury, None).await?;
+    assert_eq!(context.solana.token_account_balance(treasury).await, 3000);
+    assert_eq!(voter.deposit_amount(&context.solana, 0).await, 0);
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::{ClawbackPolicy, LockupKind};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_clawback_votes() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let voter2_authority = &context.users[2].key;
+    let voter2_mngo = context.users[2].token_accounts[0];
+    let clawback_destination = context.users[0].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+    let token_owner_record2 = realm
+        .create_token_owner_record(voter2_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    let voter2 = addin
+        .create_voter(&registrar, &token_owner_record2, &voter2_authority, &payer)
+        .await;
+
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+
+    // voter gets enough vote weight to create a proposal
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    // voter2 has a deposit that can be clawed back
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter2,
+            voter2_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::Daily,
+            None,
+            10,
+            true,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            voter2_authority,
+            voter2_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter2,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    realm
+        .cast_vote(
+            mint_governance.address,
+            &proposal,
+            &voter2,
+            &voter2_authority,
+            payer,
+            addin.update_voter_weight_record_for_vote_instruction(&registrar, &voter2, &proposal),
+        )
+        .await
+        .unwrap();
+
+    addin
+        .configure_clawback_policy(
+            &registrar,
+            &voter_authority,
+            ClawbackPolicy::RequireNoOutstandingVotes,
+        )
+        .await
+        .expect_err("only the realm authority can configure the policy");
+    addin
+        .configure_clawback_policy(
+            &registrar,
+            &realm_authority,
+            ClawbackPolicy::RequireNoOutstandingVotes,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .clawback(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            &realm_authority,
+            clawback_destination,
+            0,
+            None,
+        )
+        .await
+        .expect_err("voter2 has an outstanding vote");
+
+    realm
+        .force_relinquish_vote(
+            mint_governance.address,
+            &proposal,
+            voter2.token_owner_record,
+        )
+        .await
+        .unwrap();
+    addin
+        .release_vote_locks(
+            &registrar,
+            &voter2,
+            &[realm.vote_record_address(&proposal, voter2.token_owner_record)],
+        )
+        .await
+        .unwrap();
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    let destination_before = context
+        .solana
+        .token_account_balance(clawback_destination)
+        .await;
+    addin
+        .clawback(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            &realm_authority,
+            clawback_destination,
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        context
+            .solana
+            .token_account_balance(clawback_destination)
+            .await,
+        destination_before + 1000
+    );
+    assert_eq!(
+        mngo_voting_mint
+            .vault_balance(&context.solana, &voter2)
+            .await,
+        0
+    );
+
+    Ok(())
+}
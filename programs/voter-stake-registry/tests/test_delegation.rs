@@ -0,0 +1,356 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_delegation() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let mut voters = vec![];
+    for (user, amount) in [
+        (&context.users[1], 1000),
+        (&context.users[2], 2000),
+        (&context.users[3], 4000),
+    ] {
+        let token_owner_record = realm
+            .create_token_owner_record(user.key.pubkey(), &payer)
+            .await;
+        let voter = addin
+            .create_voter(&registrar, &token_owner_record, &user.key, &payer)
+            .await;
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                &user.key,
+                &mngo_voting_mint,
+                0,
+                LockupKind::None,
+                None,
+                0,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                &user.key,
+                user.token_accounts[0],
+                0,
+                amount,
+            )
+            .await
+            .unwrap();
+        voters.push(voter);
+    }
+    let (delegator, delegate, other) = (&voters[0], &voters[1], &voters[2]);
+    let delegator_authority = &context.users[1].key;
+    let delegator_mngo = context.users[1].token_accounts[0];
+    let delegate_authority = &context.users[2].key;
+
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &delegate,
+            &delegate_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &delegate,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+
+    addin
+        .set_delegate(
+            &registrar,
+            &delegator,
+            delegate_authority,
+            delegate.authority,
+        )
+        .await
+        .expect_err("only the voter authority can delegate");
+    addin
+        .set_delegate(
+            &registrar,
+            &delegator,
+            delegator_authority,
+            delegator.authority,
+        )
+        .await
+        .expect_err("can't delegate to self");
+    addin
+        .set_delegate(
+            &registrar,
+            &delegator,
+            delegator_authority,
+            delegate.authority,
+        )
+        .await
+        .unwrap();
+
+    // the delegator's weight moves to the delegate
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &delegator)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 0);
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &delegate)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 2000);
+
+    // delegated weight only counts for votes, where it gets locked
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &delegate_authority,
+            &delegate,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &delegate),
+        )
+        .await
+        .unwrap();
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &delegate,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[delegator],
+            Some(VoterWeightAction::CreateProposal),
+            None,
+        )
+        .await
+        .expect_err("delegations need a vote");
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[delegator],
+            None,
+            None,
+        )
+        .await
+        .expect_err("delegations need a vote");
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[delegator, delegator],
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .expect_err("delegators can't be counted twice");
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[other],
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .expect_err("other doesn't delegate");
+
+    // withdraws follow the delegate's votes
+    realm
+        .cast_vote(
+            mint_governance.address,
+            &proposal,
+            &delegate,
+            &delegate_authority,
+            payer,
+            addin.update_voter_weight_record_with_delegations_instruction(
+                &registrar,
+                &delegate,
+                &[delegator],
+                Some(VoterWeightAction::CastVote),
+                Some(&proposal),
+            ),
+        )
+        .await
+        .unwrap();
+    let vwr = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightRecord>(delegate.voter_weight_record)
+        .await;
+    assert_eq!(vwr.voter_weight, 3000);
+
+    addin
+        .withdraw(
+            &registrar,
+            &delegator,
+            &mngo_voting_mint,
+            delegator_authority,
+            delegator_mngo,
+            0,
+            1000,
+        )
+        .await
+        .expect_err("needs the delegate's token owner record");
+    addin
+        .withdraw_with_delegate(
+            &registrar,
+            &delegator,
+            &mngo_voting_mint,
+            delegator_authority,
+            delegator_mngo,
+            0,
+            1000,
+            delegate.token_owner_record,
+        )
+        .await
+        .expect_err("delegate has an outstanding vote");
+
+    // the delegator's weight stays locked until the vote is relinquished
+    let vote_record = realm.vote_record_address(&proposal, delegate.token_owner_record);
+    let delegator_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(delegator.address)
+        .await;
+    assert_eq!(delegator_data.active_votes(), 1);
+    addin
+        .release_vote_locks(&registrar, &delegator, &[vote_record])
+        .await
+        .expect_err("vote not relinquished");
+    addin
+        .set_delegate(
+            &registrar,
+            &delegator,
+            delegator_authority,
+            Pubkey::default(),
+        )
+        .await
+        .expect_err("the weight is locked for the delegate's vote");
+
+    realm
+        .force_relinquish_vote(
+            mint_governance.address,
+            &proposal,
+            delegate.token_owner_record,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .withdraw_with_delegate(
+            &registrar,
+            &delegator,
+            &mngo_voting_mint,
+            delegator_authority,
+            delegator_mngo,
+            0,
+            500,
+            delegate.token_owner_record,
+        )
+        .await
+        .expect_err("vote lock not released");
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .release_vote_locks(&registrar, &delegator, &[vote_record])
+        .await
+        .unwrap();
+    addin
+        .withdraw_with_delegate(
+            &registrar,
+            &delegator,
+            &mngo_voting_mint,
+            delegator_authority,
+            delegator_mngo,
+            0,
+            500,
+            delegate.token_owner_record,
+        )
+        .await
+        .unwrap();
+
+    // revoking is possible once the weight is no longer locked
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .set_delegate(
+            &registrar,
+            &delegator,
+            delegator_authority,
+            Pubkey::default(),
+        )
+        .await
+        .unwrap();
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &delegator)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 500);
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[delegator],
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .expect_err("no longer delegating");
+
+    Ok(())
+}
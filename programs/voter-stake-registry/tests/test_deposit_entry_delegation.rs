@@ -0,0 +1,397 @@
+use anchor_lang::AnchorDeserialize;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance::state::vote_record::VoteRecordV2;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::{LockupKind, MAX_DELEGATORS};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_deposit_entry_delegation() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    let mut voters = vec![];
+    for (user, amounts) in [
+        (&context.users[1], vec![1000, 2000]),
+        (&context.users[2], vec![100]),
+        (&context.users[3], vec![10]),
+    ] {
+        let token_owner_record = realm
+            .create_token_owner_record(user.key.pubkey(), &payer)
+            .await;
+        let voter = addin
+            .create_voter(&registrar, &token_owner_record, &user.key, &payer)
+            .await;
+        for (i, amount) in amounts.into_iter().enumerate() {
+            addin
+                .create_deposit_entry(
+                    &registrar,
+                    &voter,
+                    &user.key,
+                    &mngo_voting_mint,
+                    i as u8,
+                    LockupKind::None,
+                    None,
+                    0,
+                    false,
+                )
+                .await
+                .unwrap();
+            addin
+                .deposit(
+                    &registrar,
+                    &voter,
+                    &mngo_voting_mint,
+                    &user.key,
+                    user.token_accounts[0],
+                    i as u8,
+                    amount,
+                )
+                .await
+                .unwrap();
+        }
+        voters.push(voter);
+    }
+    let (owner, lead, other) = (&voters[0], &voters[1], &voters[2]);
+    let owner_authority = &context.users[1].key;
+    let owner_mngo = context.users[1].token_accounts[0];
+    let lead_authority = &context.users[2].key;
+
+    addin
+        .set_deposit_entry_delegate(&registrar, &owner, owner_authority, 1, owner.address)
+        .await
+        .expect_err("can't delegate to self");
+    addin
+        .set_deposit_entry_delegate(&registrar, &owner, lead_authority, 1, lead.address)
+        .await
+        .expect_err("only the voter authority can delegate");
+    addin
+        .set_deposit_entry_delegate(&registrar, &owner, owner_authority, 1, lead.address)
+        .await
+        .unwrap();
+
+    // only the delegated entry moves to the lead
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &owner)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &owner,
+            &owner_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &owner,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &lead_authority,
+            &lead,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &lead),
+        )
+        .await
+        .unwrap();
+    for voter in [lead, owner] {
+        addin
+            .create_voter_weight_snapshot(
+                &registrar,
+                voter,
+                mint_governance.address,
+                proposal.address,
+                payer,
+            )
+            .await
+            .unwrap();
+    }
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &lead,
+            lead_authority,
+            &[owner],
+            Some(VoterWeightAction::CreateProposal),
+            None,
+        )
+        .await
+        .expect_err("delegations need a vote");
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &lead,
+            lead_authority,
+            &[other],
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .expect_err("other doesn't delegate");
+
+    // withdraws from the delegated entry follow the lead's votes
+    realm
+        .cast_vote(
+            mint_governance.address,
+            &proposal,
+            &lead,
+            &lead_authority,
+            payer,
+            addin.update_voter_weight_record_with_delegations_instruction(
+                &registrar,
+                &lead,
+                &[owner],
+                Some(VoterWeightAction::CastVote),
+                Some(&proposal),
+            ),
+        )
+        .await
+        .unwrap();
+    let vwr = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightRecord>(lead.voter_weight_record)
+        .await;
+    assert_eq!(vwr.voter_weight, 2100);
+
+    addin
+        .withdraw(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            0,
+            100,
+        )
+        .await
+        .expect_err("the owner's weight is locked for the lead's vote");
+    addin
+        .withdraw(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            1,
+            500,
+        )
+        .await
+        .expect_err("needs the delegate's accounts");
+    addin
+        .withdraw_with_entry_delegate(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            1,
+            500,
+            &lead,
+        )
+        .await
+        .expect_err("delegate has an outstanding vote");
+    addin
+        .set_deposit_entry_delegate(&registrar, &owner, owner_authority, 1, Pubkey::default())
+        .await
+        .expect_err("the entry's weight is locked for the lead's vote");
+    addin
+        .internal_transfer_unlocked(&registrar, &owner, owner_authority, 1, 0, 500)
+        .await
+        .expect_err("moving the entry's tokens to the owner would let them vote twice");
+
+    // the owner votes with the entries it didn't delegate only
+    addin
+        .cast_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &owner,
+            owner_authority,
+            payer,
+        )
+        .await
+        .unwrap();
+    let vote_record_address = realm.vote_record_address(&proposal, owner.token_owner_record);
+    let vote_record_data = context.solana.get_account_data(vote_record_address).await;
+    let vote_record = VoteRecordV2::deserialize(&mut &vote_record_data[..]).unwrap();
+    assert_eq!(vote_record.voter_weight, 1000);
+    addin
+        .relinquish_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &owner,
+            owner_authority,
+            owner_authority.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // the number of delegators per update is limited
+    let grantees = (1..MAX_DELEGATORS)
+        .map(|_| Keypair::new())
+        .collect::<Vec<_>>();
+    let mut grantee_voters = vec![];
+    for chunk in grantees.chunks(4) {
+        let grants = chunk.iter().map(|k| (k.pubkey(), 1000)).collect::<Vec<_>>();
+        grantee_voters.extend(
+            addin
+                .grant_batch(
+                    &registrar,
+                    &grants,
+                    &mngo_voting_mint,
+                    LockupKind::Cliff,
+                    None,
+                    10,
+                    false,
+                    grant_funds,
+                    grant_authority,
+                    grant_authority,
+                )
+                .await
+                .unwrap(),
+        );
+    }
+    for (grantee, voter) in grantees.iter().zip(grantee_voters.iter()) {
+        addin
+            .set_deposit_entry_delegate(&registrar, voter, grantee, 0, lead.address)
+            .await
+            .unwrap();
+    }
+
+    let mut delegators = vec![owner];
+    delegators.extend(grantee_voters.iter());
+    assert_eq!(delegators.len(), MAX_DELEGATORS);
+    let vwr = addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &lead,
+            lead_authority,
+            &delegators,
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        vwr.voter_weight,
+        100 + 2000 + 1000 * (MAX_DELEGATORS as u64 - 1)
+    );
+
+    delegators.push(other);
+    addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &lead,
+            lead_authority,
+            &delegators,
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .expect_err("too many delegators");
+
+    realm
+        .force_relinquish_vote(mint_governance.address, &proposal, lead.token_owner_record)
+        .await
+        .unwrap();
+
+    addin
+        .withdraw_with_entry_delegate(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            1,
+            500,
+            &lead,
+        )
+        .await
+        .expect_err("vote lock not released");
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .release_vote_locks(
+            &registrar,
+            &owner,
+            &[realm.vote_record_address(&proposal, lead.token_owner_record)],
+        )
+        .await
+        .unwrap();
+    addin
+        .withdraw(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            0,
+            100,
+        )
+        .await
+        .unwrap();
+    addin
+        .withdraw_with_entry_delegate(
+            &registrar,
+            &owner,
+            &mngo_voting_mint,
+            owner_authority,
+            owner_mngo,
+            1,
+            500,
+            &lead,
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use anchor_lang::AnchorDeserialize;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance::state::vote_record::VoteRecordV2;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_governance_cpi() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let mut voters = vec![];
+    for (user, amount) in [(&context.users[1], 10000), (&context.users[2], 5000)] {
+        let token_owner_record = realm
+            .create_token_owner_record(user.key.pubkey(), &payer)
+            .await;
+        let voter = addin
+            .create_voter(&registrar, &token_owner_record, &user.key, &payer)
+            .await;
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                &user.key,
+                &mngo_voting_mint,
+                0,
+                LockupKind::None,
+                None,
+                0,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                &user.key,
+                user.token_accounts[0],
+                0,
+                amount,
+            )
+            .await
+            .unwrap();
+        voters.push(voter);
+    }
+    let (voter, voter2) = (&voters[0], &voters[1]);
+    let voter_authority = &context.users[1].key;
+    let voter2_authority = &context.users[2].key;
+    let voter2_mngo = context.users[2].token_accounts[0];
+
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+
+    let proposal = addin
+        .create_proposal(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &voter,
+            &voter_authority,
+            payer,
+        )
+        .await
+        .unwrap();
+    let vwr = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightRecord>(voter.voter_weight_record)
+        .await;
+    assert_eq!(vwr.voter_weight, 10000);
+    assert_eq!(vwr.weight_action, Some(VoterWeightAction::CreateProposal));
+    assert_eq!(vwr.weight_action_target, Some(mint_governance.address));
+
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter2,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    addin
+        .cast_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &voter2,
+            &voter2_authority,
+            payer,
+        )
+        .await
+        .unwrap();
+    let vwr = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightRecord>(voter2.voter_weight_record)
+        .await;
+    assert_eq!(vwr.voter_weight, 5000);
+    assert_eq!(vwr.weight_action, Some(VoterWeightAction::CastVote));
+    assert_eq!(vwr.weight_action_target, Some(proposal.address));
+
+    let vote_record_address = realm.vote_record_address(&proposal, voter2.token_owner_record);
+    let vote_record_data = context.solana.get_account_data(vote_record_address).await;
+    let vote_record = VoteRecordV2::deserialize(&mut &vote_record_data[..]).unwrap();
+    assert_eq!(vote_record.voter_weight, 5000);
+    let voter2_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter2.address)
+        .await;
+    assert_eq!(voter2_data.active_votes(), 1);
+
+    addin
+        .withdraw(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            &voter2_authority,
+            voter2_mngo,
+            0,
+            5000,
+        )
+        .await
+        .expect_err("voted on an active proposal");
+
+    addin
+        .relinquish_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &voter2,
+            &voter2_authority,
+            voter2_authority.pubkey(),
+        )
+        .await
+        .unwrap();
+    let voter2_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter2.address)
+        .await;
+    assert_eq!(voter2_data.active_votes(), 0);
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .withdraw(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            &voter2_authority,
+            voter2_mngo,
+            0,
+            5000,
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
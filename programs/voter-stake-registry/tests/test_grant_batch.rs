@@ -0,0 +1,179 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_grant_batch() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter2_authority = &context.users[2].key;
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            2.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    // use up all deposit entries
+    for i in 0..32 {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                i,
+                LockupKind::None,
+                None,
+                0,
+                false,
+            )
+            .await
+            .unwrap();
+    }
+
+    let grants = vec![
+        (voter2_authority.pubkey(), 24000),
+        (voter_authority.pubkey(), 12000),
+    ];
+
+    let funds_before = context.solana.token_account_balance(grant_funds).await;
+
+    // the voter authority is not allowed to batch grant
+    addin
+        .grant_batch(
+            &registrar,
+            &grants[1..],
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+            grant_funds,
+            grant_authority,
+            voter_authority,
+        )
+        .await
+        .expect_err("voter authority can't batch grant");
+
+    // no grant is made if one of the recipients is full
+    addin
+        .grant_batch(
+            &registrar,
+            &grants,
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .expect_err("voter has no free deposit entry");
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before
+    );
+
+    addin
+        .close_deposit_entry(&voter, voter_authority, 5)
+        .await
+        .unwrap();
+
+    let voters = addin
+        .grant_batch(
+            &registrar,
+            &grants,
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .unwrap();
+    assert_eq!(voters[1].address, voter.address);
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before - 36000
+    );
+    assert_eq!(voters[0].deposit_amount(&context.solana, 0).await, 24000);
+    assert_eq!(voter.deposit_amount(&context.solana, 5).await, 12000);
+    assert_eq!(
+        mngo_voting_mint
+            .vault_balance(&context.solana, &voters[0])
+            .await,
+        24000
+    );
+
+    let voter2_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voters[0].address)
+        .await;
+    assert_eq!(voter2_data.voter_authority, voter2_authority.pubkey());
+    assert_eq!(voter2_data.registrar, registrar.address);
+    let deposit = &voter2_data.deposits[0];
+    assert_eq!(deposit.is_used, true);
+    assert_eq!(deposit.amount_initially_locked_native, 24000);
+    assert_eq!(deposit.allow_clawback, true);
+    assert_eq!(deposit.lockup.kind, LockupKind::Monthly);
+    assert_eq!(deposit.lockup.periods_total().unwrap(), 12);
+    assert_eq!(deposit.grantor, grant_authority.pubkey());
+    assert_eq!(voter2_data.deposits[1].is_used, false);
+
+    // the new voter weight record is usable
+    addin
+        .update_voter_weight_record(&registrar, &voters[0])
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_grant_funded_over_time() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    let day = 24 * 60 * 60;
+    let now = context.solana.get_clock().await.unix_timestamp as u64;
+    addin
+        .grant_funded_over_time(
+            &registrar,
+            voter_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Daily,
+            Some(now - 2 * day - 60),
+            10,
+            false,
+            10000,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .unwrap();
+    addin
+        .grant_funded_over_time(
+            &registrar,
+            voter_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Constant,
+            None,
+            10,
+            false,
+            10000,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .expect_err("constant lockups never vest");
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    let deposit = &voter_data.deposits[0];
+    assert_eq!(deposit.is_used, true);
+    assert_eq!(deposit.amount_deposited_native, 0);
+    assert_eq!(deposit.grant_promised_native, 10000);
+    assert_eq!(deposit.grant_funded_native, 0);
+    assert_eq!(
+        mngo_voting_mint
+            .vault_balance(&context.solana, &voter)
+            .await,
+        0
+    );
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 0);
+
+    let fund_grant = |authority: &Keypair, amount: u64| {
+        addin.fund_grant(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            grant_funds,
+            grant_authority,
+            authority,
+            0,
+            amount,
+        )
+    };
+
+    fund_grant(grant_authority, 2001)
+        .await
+        .expect_err("only 2000 have vested");
+    fund_grant(voter_authority, 1500)
+        .await
+        .expect_err("voter can't fund the grant");
+    fund_grant(grant_authority, 1500).await.unwrap();
+    fund_grant(grant_authority, 501)
+        .await
+        .expect_err("only 2000 have vested");
+    fund_grant(grant_authority, 500).await.unwrap();
+
+    // funded tokens are unlocked and only count with baseline vote weight
+    assert_eq!(
+        mngo_voting_mint
+            .vault_balance(&context.solana, &voter)
+            .await,
+        2000
+    );
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 2000);
+
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .expect_err("can't deposit into a grant funded over time");
+    addin
+        .reset_lockup(
+            &registrar,
+            &voter,
+            voter_authority,
+            0,
+            LockupKind::Daily,
+            20,
+        )
+        .await
+        .expect_err("can't change the vesting schedule");
+
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            2000,
+        )
+        .await
+        .unwrap();
+
+    // one more day vests
+    addin
+        .set_time_offset(&registrar, &realm_authority, day as i64)
+        .await;
+    fund_grant(grant_authority, 1001)
+        .await
+        .expect_err("only 3000 have vested");
+    fund_grant(grant_authority, 1000).await.unwrap();
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    let deposit = &voter_data.deposits[0];
+    assert_eq!(deposit.amount_deposited_native, 1000);
+    assert_eq!(deposit.grant_funded_native, 3000);
+    assert_eq!(deposit.amount_initially_locked_native, 0);
+
+    Ok(())
+}
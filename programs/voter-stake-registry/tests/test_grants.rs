@@ -1,7 +1,7 @@
 use anchor_spl::token::TokenAccount;
 use program_test::*;
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
 use voter_stake_registry::state::LockupKind;
 
 mod program_test;
@@ -132,6 +132,8 @@ async fn test_grants() -> Result<(), TransportError> {
     assert_eq!(deposit.allow_clawback, true);
     assert_eq!(deposit.lockup.kind, LockupKind::Monthly);
     assert_eq!(deposit.lockup.periods_total().unwrap(), 12);
+    assert_eq!(deposit.grantor, realm_authority.pubkey());
+    assert_eq!(voter_data.deposits[0].grantor, Pubkey::default());
 
     // grant funds with a start time in the past
     // by the voter authority itself
@@ -169,6 +171,8 @@ async fn test_grants() -> Result<(), TransportError> {
     assert_eq!(deposit.lockup.periods_total().unwrap(), 12);
     assert_eq!(deposit.lockup.periods_left(now as i64).unwrap(), 10);
     assert_eq!(deposit.amount_unlocked(now as i64), 2000);
+    // grants to oneself are regular deposits
+    assert_eq!(deposit.grantor, Pubkey::default());
 
     Ok(())
 }
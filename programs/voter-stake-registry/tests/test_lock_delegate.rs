@@ -0,0 +1,223 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_lock_delegate() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    for index in 0..2 {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                index,
+                LockupKind::None,
+                None,
+                0,
+                false,
+            )
+            .await
+            .unwrap();
+    }
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    let lock_delegate = Keypair::new();
+    addin
+        .set_lock_delegate(
+            &registrar,
+            &voter,
+            &lock_delegate,
+            0,
+            lock_delegate.pubkey(),
+        )
+        .await
+        .expect_err("only the voter authority can set a lock delegate");
+    addin
+        .set_lock_delegate(
+            &registrar,
+            &voter,
+            voter_authority,
+            0,
+            lock_delegate.pubkey(),
+        )
+        .await
+        .unwrap();
+    addin
+        .set_lock_delegate(
+            &registrar,
+            &voter,
+            voter_authority,
+            0,
+            voter_authority.pubkey(),
+        )
+        .await
+        .expect_err("the lock delegate can't be replaced");
+
+    // clawback must stay possible
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            2,
+            LockupKind::Cliff,
+            None,
+            1,
+            true,
+        )
+        .await
+        .unwrap();
+    addin
+        .set_lock_delegate(
+            &registrar,
+            &voter,
+            voter_authority,
+            2,
+            lock_delegate.pubkey(),
+        )
+        .await
+        .expect_err("clawback deposit entries can't be locked");
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    assert_eq!(voter_data.deposits[0].lock_delegate, lock_delegate.pubkey());
+
+    // the deposit entry is kept in place
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1,
+        )
+        .await
+        .expect_err("locked by delegate");
+    addin
+        .internal_transfer_unlocked(&registrar, &voter, voter_authority, 0, 1, 1)
+        .await
+        .expect_err("locked by delegate");
+    addin
+        .reset_lockup(&registrar, &voter, voter_authority, 0, LockupKind::Cliff, 1)
+        .await
+        .expect_err("locked by delegate");
+    addin
+        .close_deposit_entry(&voter, voter_authority, 0)
+        .await
+        .expect_err("locked by delegate");
+
+    // depositing more is fine
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            500,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .release_lock_delegate(&voter, voter_authority, 0)
+        .await
+        .expect_err("only the lock delegate can release");
+    addin
+        .release_lock_delegate(&voter, &lock_delegate, 0)
+        .await
+        .unwrap();
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    assert!(!voter_data.deposits[0].has_lock_delegate());
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1500,
+        )
+        .await
+        .unwrap();
+    addin
+        .close_deposit_entry(&voter, voter_authority, 0)
+        .await
+        .unwrap();
+
+    Ok(())
+}
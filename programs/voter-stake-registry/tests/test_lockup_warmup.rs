@@ -0,0 +1,163 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_lockup_warmup() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let day = 24 * 60 * 60;
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            10 * day as u64,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    // a cliff lockup starting in 5 days, lasting longer than the saturation
+    let start_ts = context.solana.get_clock().await.unix_timestamp + 5 * day;
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::Cliff,
+            Some(start_ts as u64),
+            20,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            10000,
+        )
+        .await
+        .unwrap();
+
+    // without warmup, the future-dated lockup gets the full bonus right away
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 20000);
+
+    addin
+        .configure_lockup_warmup(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(1.5),
+        )
+        .await
+        .expect_err("factor must be a fraction");
+
+    addin
+        .configure_lockup_warmup(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(0.0),
+        )
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 10000);
+
+    addin
+        .configure_lockup_warmup(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(0.5),
+        )
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 15000);
+
+    // once the lockup started, the full bonus applies
+    addin
+        .set_time_offset(&registrar, &realm_authority, 6 * day)
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 20000);
+
+    // disabling the warmup is possible too
+    addin
+        .configure_lockup_warmup(&registrar, &realm_authority, 0, &mngo_voting_mint, None)
+        .await
+        .unwrap();
+    addin.set_time_offset(&registrar, &realm_authority, 0).await;
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 20000);
+
+    Ok(())
+}
@@ -6,14 +6,6 @@ use voter_stake_registry::state::LockupKind;
 
 mod program_test;
 
-fn deserialize_event<T: anchor_lang::Event>(event: &str) -> Option<T> {
-    let data = base64::decode(event).ok()?;
-    if data.len() < 8 || data[0..8] != T::discriminator() {
-        return None;
-    }
-    T::try_from_slice(&data[8..]).ok()
-}
-
 #[allow(unaligned_references)]
 #[tokio::test]
 async fn test_print_event() -> Result<(), TransportError> {
@@ -0,0 +1,263 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::{LockupKind, MaxVoteWeightSource};
+
+mod program_test;
+
+async fn max_vote_weight(context: &TestContext, registrar: &RegistrarCookie) -> u64 {
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    context
+        .addin
+        .update_max_vote_weight(registrar, &[])
+        .await
+        .unwrap()
+}
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_max_vote_weight_deposits() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let grantee_authority = &context.users[2].key;
+    let grant_funds = context.users[0].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    addin
+        .configure_max_vote_weight_source(
+            &registrar,
+            &voter_authority,
+            MaxVoteWeightSource::Deposits,
+        )
+        .await
+        .expect_err("only the realm authority may change the source");
+    addin
+        .configure_max_vote_weight_source(
+            &registrar,
+            &realm_authority,
+            MaxVoteWeightSource::Deposits,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(max_vote_weight(&context, &registrar).await, 0);
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1000);
+
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            1,
+            LockupKind::Cliff,
+            None,
+            10,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            1,
+            500,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1500 + 500);
+
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            200,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1300 + 500);
+
+    // locking up the unlocked deposit adds its lockup weight
+    addin
+        .internal_transfer_unlocked(&registrar, &voter, voter_authority, 0, 1, 300)
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1300 + 500);
+    addin
+        .reset_lockup(
+            &registrar,
+            &voter,
+            voter_authority,
+            1,
+            LockupKind::Cliff,
+            10,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1300 + 800);
+
+    let grantee = addin
+        .grant(
+            &registrar,
+            grantee_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            None,
+            12,
+            true,
+            3000,
+            grant_funds,
+            payer,
+            &realm_authority,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 4300 + 3800);
+
+    addin
+        .clawback(
+            &registrar,
+            &grantee,
+            &mngo_voting_mint,
+            &realm_authority,
+            grant_funds,
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1300 + 800);
+
+    // selecting the source again resets the totals, existing voters must be synced
+    addin
+        .configure_max_vote_weight_source(&registrar, &realm_authority, MaxVoteWeightSource::Supply)
+        .await
+        .unwrap();
+    addin
+        .sync_deposit_totals(&registrar, &voter)
+        .await
+        .expect_err("deposit totals are only tracked with the Deposits source");
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .configure_max_vote_weight_source(
+            &registrar,
+            &realm_authority,
+            MaxVoteWeightSource::Deposits,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 0);
+
+    // changes to deposits of voters that aren't synced are not counted
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            100,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 0);
+
+    addin.sync_deposit_totals(&registrar, &voter).await.unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1400 + 800);
+    addin.sync_deposit_totals(&registrar, &voter).await.unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1400 + 800);
+
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            400,
+        )
+        .await
+        .unwrap();
+    assert_eq!(max_vote_weight(&context, &registrar).await, 1000 + 800);
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::{LockupKind, Registrar, RegistrarV0, VotingMintConfigV0};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_migrate_registrar() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            2.0,
+            365 * 24 * 60 * 60,
+            Some(payer.pubkey()),
+            None,
+        )
+        .await;
+
+    addin
+        .migrate_registrar(&registrar, payer)
+        .await
+        .expect_err("registrar already has the current layout");
+
+    // rewrite the registrar in the v0.2.4 layout
+    let current = context
+        .solana
+        .get_account::<Registrar>(registrar.address)
+        .await;
+    let mut legacy: RegistrarV0 = bytemuck::Zeroable::zeroed();
+    legacy.governance_program_id = current.governance_program_id;
+    legacy.realm = current.realm;
+    legacy.realm_governing_token_mint = current.realm_governing_token_mint;
+    legacy.realm_authority = current.realm_authority;
+    legacy.time_offset = current.time_offset;
+    legacy.bump = current.bump;
+    let config = current.voting_mints[0];
+    legacy.voting_mints[0] = VotingMintConfigV0 {
+        mint: config.mint,
+        grant_authority: config.grant_authority,
+        baseline_vote_weight_scaled_factor: config.baseline_vote_weight_scaled_factor,
+        max_extra_lockup_vote_weight_scaled_factor: config
+            .max_extra_lockup_vote_weight_scaled_factor,
+        lockup_saturation_secs: config.lockup_saturation_secs,
+        digit_shift: config.digit_shift,
+        reserved1: [0; 7],
+        reserved2: [0; 7],
+    };
+    let mut data = context.solana.get_account_data(registrar.address).await;
+    data.truncate(8);
+    data.extend_from_slice(bytemuck::bytes_of(&legacy));
+    context
+        .solana
+        .set_account_data(registrar.address, &data)
+        .await;
+
+    addin
+        .update_max_vote_weight(&registrar, &[])
+        .await
+        .expect_err("registrar must be migrated first");
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin.migrate_registrar(&registrar, payer).await.unwrap();
+
+    let data = context.solana.get_account_data(registrar.address).await;
+    assert_eq!(data.len(), 8 + std::mem::size_of::<Registrar>());
+    let migrated = context
+        .solana
+        .get_account::<Registrar>(registrar.address)
+        .await;
+    assert_eq!(
+        migrated.governance_program_id,
+        current.governance_program_id
+    );
+    assert_eq!(migrated.realm, current.realm);
+    assert_eq!(
+        migrated.realm_governing_token_mint,
+        current.realm_governing_token_mint
+    );
+    assert_eq!(migrated.realm_authority, current.realm_authority);
+    assert_eq!(migrated.bump, current.bump);
+    let migrated_config = migrated.voting_mints[0];
+    assert_eq!(migrated_config.mint, config.mint);
+    assert_eq!(migrated_config.grant_authority, payer.pubkey());
+    assert_eq!(
+        migrated_config.baseline_vote_weight_scaled_factor,
+        1_000_000_000
+    );
+    assert_eq!(
+        migrated_config.max_extra_lockup_vote_weight_scaled_factor,
+        2_000_000_000
+    );
+    assert_eq!(migrated_config.lockup_saturation_secs, 365 * 24 * 60 * 60);
+    assert!(!migrated.voting_mints[1].in_use());
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .migrate_registrar(&registrar, payer)
+        .await
+        .expect_err("already migrated");
+
+    // the migrated registrar works as before
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+
+    Ok(())
+}
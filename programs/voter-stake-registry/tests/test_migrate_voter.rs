@@ -0,0 +1,189 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::{DepositEntryV0, LockupKind, Voter, VoterV0};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_migrate_voter() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    for (index, kind, periods, amount) in [
+        (0, LockupKind::None, 0, 1000),
+        (1, LockupKind::Cliff, 10, 500),
+    ] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                index,
+                kind,
+                None,
+                periods,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                voter_authority,
+                voter_mngo,
+                index,
+                amount,
+            )
+            .await
+            .unwrap();
+    }
+
+    addin
+        .migrate_voter(&voter, payer)
+        .await
+        .expect_err("voter already has the current layout");
+
+    // rewrite the voter in the v0.2.4 layout
+    let current = context.solana.get_account::<Voter>(voter.address).await;
+    let mut legacy: VoterV0 = bytemuck::Zeroable::zeroed();
+    legacy.voter_authority = current.voter_authority;
+    legacy.registrar = current.registrar;
+    legacy.voter_bump = current.voter_bump;
+    legacy.voter_weight_record_bump = current.voter_weight_record_bump;
+    for (legacy_deposit, deposit) in legacy.deposits.iter_mut().zip(current.deposits.iter()) {
+        *legacy_deposit = DepositEntryV0 {
+            lockup: deposit.lockup,
+            amount_deposited_native: deposit.amount_deposited_native,
+            amount_initially_locked_native: deposit.amount_initially_locked_native,
+            is_used: deposit.is_used,
+            allow_clawback: deposit.allow_clawback,
+            voting_mint_config_idx: deposit.voting_mint_config_idx,
+            reserved: [0; 29],
+        };
+    }
+    let mut data = context.solana.get_account_data(voter.address).await;
+    data.truncate(8);
+    data.extend_from_slice(bytemuck::bytes_of(&legacy));
+    context.solana.set_account_data(voter.address, &data).await;
+
+    addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .expect_err("voter must be migrated first");
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin.migrate_voter(&voter, payer).await.unwrap();
+
+    let data = context.solana.get_account_data(voter.address).await;
+    assert_eq!(data.len(), 8 + std::mem::size_of::<Voter>());
+    let migrated = context.solana.get_account::<Voter>(voter.address).await;
+    assert_eq!(migrated.voter_authority, voter_authority.pubkey());
+    assert_eq!(migrated.registrar, registrar.address);
+    assert_eq!(migrated.voter_bump, current.voter_bump);
+    assert_eq!(
+        migrated.voter_weight_record_bump,
+        current.voter_weight_record_bump
+    );
+    assert_eq!(migrated.active_votes(), 0);
+    for (migrated_deposit, deposit) in migrated.deposits.iter().zip(current.deposits.iter()) {
+        assert_eq!(migrated_deposit.is_used, deposit.is_used);
+        assert_eq!(
+            migrated_deposit.amount_deposited_native,
+            deposit.amount_deposited_native
+        );
+        assert_eq!(
+            migrated_deposit.amount_initially_locked_native,
+            deposit.amount_initially_locked_native
+        );
+        assert_eq!(migrated_deposit.lockup.kind, deposit.lockup.kind);
+        assert!(!migrated_deposit.is_grant());
+        assert!(!migrated_deposit.has_lock_delegate());
+        assert!(!migrated_deposit.is_delegated());
+    }
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .migrate_voter(&voter, payer)
+        .await
+        .expect_err("already migrated");
+
+    // the migrated voter works as before
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1500);
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            1,
+            1,
+        )
+        .await
+        .expect_err("cliff lockup is kept");
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+    assert_eq!(voter.deposit_amount(&context.solana, 0).await, 0);
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_revoke_grant() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    let now = context.solana.get_clock().await.unix_timestamp as u64;
+    let grant = |start_ts: Option<u64>, amount: u64| {
+        addin.grant(
+            &registrar,
+            voter_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Monthly,
+            start_ts,
+            12,
+            false,
+            amount,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+    };
+    let funds_before = context.solana.token_account_balance(grant_funds).await;
+
+    let voter = VoterCookie {
+        token_owner_record: token_owner_record.address,
+        ..grant(Some(now + 24 * 60 * 60), 12000).await.unwrap()
+    };
+    grant(None, 6000).await.unwrap();
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before - 18000
+    );
+
+    // a deposit entry the voter created with a future start
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            2,
+            LockupKind::Cliff,
+            Some(now + 24 * 60 * 60),
+            1,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            2,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .revoke_grant(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &realm_authority,
+            grant_funds,
+            0,
+        )
+        .await
+        .expect_err("only the grantor can revoke");
+    addin
+        .revoke_grant(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            grant_authority,
+            grant_funds,
+            1,
+        )
+        .await
+        .expect_err("grant has already started");
+    addin
+        .revoke_grant(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            2,
+        )
+        .await
+        .expect_err("deposit entry is not a grant");
+
+    // the voter votes with the granted weight, recorded in checkpoints since
+    // the voter was created by grant
+    addin
+        .create_voter_weight_checkpoints(&registrar, &voter, payer)
+        .await
+        .unwrap();
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    realm
+        .cast_vote(
+            mint_governance.address,
+            &proposal,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_vote_instruction(&registrar, &voter, &proposal),
+        )
+        .await
+        .unwrap();
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    assert_eq!(voter_data.active_votes(), 1);
+
+    // the voter can't add tokens to a grant that may still be revoked
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .expect_err("grant can still be revoked");
+
+    // the outstanding vote doesn't block revoking, like for clawback
+    addin
+        .revoke_grant(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            grant_authority,
+            grant_funds,
+            0,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        context.solana.token_account_balance(grant_funds).await,
+        funds_before - 6000
+    );
+    assert_eq!(
+        mngo_voting_mint
+            .vault_balance(&context.solana, &voter)
+            .await,
+        7000
+    );
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    assert_eq!(voter_data.deposits[0].is_used, false);
+    assert_eq!(voter_data.deposits[1].amount_deposited_native, 6000);
+    let registrar_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Registrar>(registrar.address)
+        .await;
+    assert_eq!(registrar_data.voting_mints[0].deposited_native_total, 7000);
+
+    // the freed deposit entry is reused by the next grant
+    grant(Some(now + 24 * 60 * 60), 3000).await.unwrap();
+    assert_eq!(voter.deposit_amount(&context.solana, 0).await, 3000);
+
+    Ok(())
+}
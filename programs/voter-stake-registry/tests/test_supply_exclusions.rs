@@ -0,0 +1,152 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_supply_exclusions() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_mint = context.mints[0].pubkey.unwrap();
+    let usdc_mint = context.mints[1].pubkey.unwrap();
+    addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let circulating = context
+        .solana
+        .create_token_account(&payer.pubkey(), mngo_mint)
+        .await;
+    let treasury = context
+        .solana
+        .create_token_account(&realm_authority.pubkey(), mngo_mint)
+        .await;
+    let usdc_account = context
+        .solana
+        .create_token_account(&realm_authority.pubkey(), usdc_mint)
+        .await;
+    context
+        .solana
+        .mint_to(&context.mints[0], circulating, 1000)
+        .await;
+    context
+        .solana
+        .mint_to(&context.mints[0], treasury, 500)
+        .await;
+
+    assert_eq!(
+        addin
+            .update_max_vote_weight(&registrar, &[mngo_mint])
+            .await
+            .unwrap(),
+        2 * 1500
+    );
+
+    addin
+        .configure_supply_exclusions(&registrar, &realm_authority, payer, &[usdc_account])
+        .await
+        .expect_err("usdc is not a voting mint");
+    addin
+        .configure_supply_exclusions(&registrar, &realm_authority, payer, &[treasury, treasury])
+        .await
+        .expect_err("duplicate exclusion");
+    addin
+        .configure_supply_exclusions(&registrar, &realm_authority, payer, &[treasury])
+        .await
+        .unwrap();
+
+    let supply_exclusions = registrar.supply_exclusions_address(&addin.program_id);
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .update_max_vote_weight(&registrar, &[mngo_mint])
+        .await
+        .expect_err("exclusions account is missing");
+    addin
+        .update_max_vote_weight(&registrar, &[mngo_mint, supply_exclusions])
+        .await
+        .expect_err("excluded token account is missing");
+    assert_eq!(
+        addin
+            .update_max_vote_weight(&registrar, &[mngo_mint, supply_exclusions, treasury])
+            .await
+            .unwrap(),
+        2 * 1000
+    );
+
+    // closed excluded accounts count as empty
+    let empty_treasury = context
+        .solana
+        .create_token_account(&realm_authority.pubkey(), mngo_mint)
+        .await;
+    addin
+        .configure_supply_exclusions(
+            &registrar,
+            &realm_authority,
+            payer,
+            &[treasury, empty_treasury],
+        )
+        .await
+        .unwrap();
+    context
+        .solana
+        .close_token_account(empty_treasury, &realm_authority)
+        .await;
+    assert_eq!(
+        addin
+            .update_max_vote_weight(
+                &registrar,
+                &[mngo_mint, supply_exclusions, treasury, empty_treasury]
+            )
+            .await
+            .unwrap(),
+        2 * 1000
+    );
+
+    // disable again
+    addin
+        .configure_supply_exclusions(&registrar, &realm_authority, payer, &[])
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    assert_eq!(
+        addin
+            .update_max_vote_weight(&registrar, &[mngo_mint])
+            .await
+            .unwrap(),
+        2 * 1500
+    );
+
+    Ok(())
+}
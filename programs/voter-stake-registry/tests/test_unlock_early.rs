@@ -0,0 +1,209 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_unlock_early() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_mint = context.mints[0].pubkey.unwrap();
+    let grant_authority = &context.users[3].key;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            5 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+    let treasury = context
+        .solana
+        .create_token_account(&realm_authority.pubkey(), mngo_mint)
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    // deposit 0 can't be unlocked early, deposit 1 can
+    for (index, allow_clawback) in [(0, true), (1, false)] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                index,
+                LockupKind::Cliff,
+                None,
+                10,
+                allow_clawback,
+            )
+            .await
+            .unwrap();
+    }
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            1,
+            10000,
+        )
+        .await
+        .unwrap();
+
+    let unlock_early = |index: u8, penalty_destination, amount: u64| {
+        addin.unlock_early(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            penalty_destination,
+            index,
+            amount,
+        )
+    };
+
+    unlock_early(1, treasury, 2000)
+        .await
+        .expect_err("unlock early is not enabled");
+
+    addin
+        .configure_unlock_early(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(treasury),
+            1.5,
+        )
+        .await
+        .expect_err("penalty can't exceed the amount");
+    addin
+        .configure_unlock_early(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            Some(treasury),
+            0.5,
+        )
+        .await
+        .unwrap();
+
+    unlock_early(1, voter_mngo, 2000)
+        .await
+        .expect_err("wrong penalty destination");
+    unlock_early(1, treasury, 10001)
+        .await
+        .expect_err("not enough locked tokens");
+    unlock_early(0, treasury, 0)
+        .await
+        .expect_err("clawback deposits can't be unlocked early");
+
+    // deposit 2 is a grant that can still be revoked
+    let now = context.solana.get_clock().await.unix_timestamp as u64;
+    addin
+        .grant(
+            &registrar,
+            voter_authority.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Cliff,
+            Some(now + 24 * 60 * 60),
+            10,
+            false,
+            1000,
+            context.users[3].token_accounts[0],
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .unwrap();
+    unlock_early(2, treasury, 1000)
+        .await
+        .expect_err("revocable grants can't be unlocked early");
+
+    // the first attempt with the same arguments failed
+    context.solana.advance_clock_by_slots(2).await;
+    let balance_before = context.solana.token_account_balance(voter_mngo).await;
+    unlock_early(1, treasury, 2000).await.unwrap();
+    // locked for longer than the saturation time, the full penalty applies
+    assert_eq!(context.solana.token_account_balance(treasury).await, 1000);
+    assert_eq!(
+        context.solana.token_account_balance(voter_mngo).await,
+        balance_before + 1000
+    );
+
+    let voter_data = context
+        .solana
+        .get_account::<voter_stake_registry::state::Voter>(voter.address)
+        .await;
+    let deposit = &voter_data.deposits[1];
+    assert_eq!(deposit.amount_deposited_native, 8000);
+    assert_eq!(deposit.amount_initially_locked_native, 8000);
+
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    // the granted tokens count in full as well
+    assert_eq!(vwr.voter_weight, 2 * 8000 + 2 * 1000);
+
+    // disabling forbids it again
+    addin
+        .configure_unlock_early(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            None,
+            0.5,
+        )
+        .await
+        .unwrap();
+    unlock_early(1, treasury, 2000)
+        .await
+        .expect_err("unlock early is not enabled");
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::{LockupKind, VoterWeightRecord};
+
+mod program_test;
+
+async fn get_record(solana: &SolanaCookie, voter: &VoterCookie) -> VoterWeightRecord {
+    solana
+        .get_account::<VoterWeightRecord>(voter.voter_weight_record)
+        .await
+}
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_update_voter_weight_records() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    // a voter with decaying lockup weight and one with constant weight
+    let mut voters = vec![];
+    for (user, lockup_kind) in [
+        (&context.users[1], LockupKind::Cliff),
+        (&context.users[2], LockupKind::None),
+    ] {
+        let token_owner_record = realm
+            .create_token_owner_record(user.key.pubkey(), &payer)
+            .await;
+        let voter = addin
+            .create_voter(&registrar, &token_owner_record, &user.key, &payer)
+            .await;
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                &user.key,
+                &mngo_voting_mint,
+                0,
+                lockup_kind,
+                None,
+                365,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                &user.key,
+                user.token_accounts[0],
+                0,
+                1000,
+            )
+            .await
+            .unwrap();
+        voters.push(voter);
+    }
+    let (locked, unlocked) = (&voters[0], &voters[1]);
+
+    // the records written by create_voter have no action, they are kept empty
+    // since spl-governance would accept them for votes
+    let events = addin
+        .update_voter_weight_records(
+            &registrar,
+            &[
+                (locked.address, locked.voter_weight_record),
+                (unlocked.address, unlocked.voter_weight_record),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 0);
+    assert_eq!(get_record(&context.solana, locked).await.voter_weight, 0);
+
+    for voter in [locked, unlocked] {
+        addin
+            .update_voter_weight_record(&registrar, voter)
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_record(&context.solana, locked).await.voter_weight, 2000);
+    assert_eq!(
+        get_record(&context.solana, unlocked).await.voter_weight,
+        1000
+    );
+
+    // after the weight decayed, only the changed record produces an event and
+    // mismatched pairs are skipped
+    addin
+        .set_time_offset(&registrar, &realm_authority, 365 * 24 * 60 * 60 / 4)
+        .await;
+    let events = addin
+        .update_voter_weight_records(
+            &registrar,
+            &[
+                (locked.address, unlocked.voter_weight_record),
+                (Pubkey::new_unique(), Pubkey::new_unique()),
+                (locked.address, locked.voter_weight_record),
+                (unlocked.address, unlocked.voter_weight_record),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].voter, locked.address);
+    assert_eq!(events[0].voter_authority, locked.authority);
+    assert_eq!(events[0].previous_voter_weight, 2000);
+    assert_eq!(events[0].voter_weight, 1750);
+    assert_eq!(get_record(&context.solana, locked).await.voter_weight, 1750);
+    assert_eq!(
+        get_record(&context.solana, unlocked).await.voter_weight,
+        1000
+    );
+
+    Ok(())
+}
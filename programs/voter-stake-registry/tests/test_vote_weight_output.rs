@@ -0,0 +1,122 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_vote_weight_output() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_mint = context.mints[0].pubkey.unwrap();
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    // a supply that only overflows a u64 once the lockup bonus is added
+    let treasury = context
+        .solana
+        .create_token_account(&realm_authority.pubkey(), mngo_mint)
+        .await;
+    context
+        .solana
+        .mint_to(&context.mints[0], treasury, 10_000_000_000_000_000_000)
+        .await;
+    addin
+        .update_max_vote_weight(&registrar, &[mngo_mint])
+        .await
+        .expect_err("max vote weight overflows");
+
+    addin
+        .configure_vote_weight_output(&registrar, &realm_authority, -1, &[])
+        .await
+        .expect_err("voting mint is missing");
+    addin
+        .configure_vote_weight_output(&registrar, &realm_authority, -1, &[mngo_mint])
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    assert_eq!(
+        addin
+            .update_max_vote_weight(&registrar, &[mngo_mint])
+            .await
+            .unwrap(),
+        2_000_000_000_000_000_000
+    );
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            10005,
+        )
+        .await
+        .unwrap();
+
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+
+    Ok(())
+}
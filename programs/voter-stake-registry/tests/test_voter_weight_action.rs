@@ -0,0 +1,166 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weight_action() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    for (index, kind, periods, amount) in [
+        (0, LockupKind::None, 0, 1000),
+        (1, LockupKind::Cliff, 5, 2000),
+        (2, LockupKind::Constant, 20, 4000),
+    ] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                index,
+                kind,
+                None,
+                periods,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                voter_authority,
+                voter_mngo,
+                index,
+                amount,
+            )
+            .await
+            .unwrap();
+    }
+
+    let day = 24 * 60 * 60;
+    addin
+        .configure_voter_weight_action(
+            &registrar,
+            voter_authority,
+            VoterWeightAction::CreateProposal,
+            10 * day,
+        )
+        .await
+        .expect_err("only the realm authority can configure rules");
+    addin
+        .configure_voter_weight_action(
+            &registrar,
+            &realm_authority,
+            VoterWeightAction::CreateProposal,
+            10 * day,
+        )
+        .await
+        .unwrap();
+
+    // votes need a proposal of the realm as target
+    addin
+        .update_voter_weight_record_for_action(
+            &registrar,
+            &voter,
+            Some(VoterWeightAction::CastVote),
+            Some(Pubkey::new_unique()),
+        )
+        .await
+        .expect_err("the target must be a proposal of the realm");
+    addin
+        .update_voter_weight_record_for_action(
+            &registrar,
+            &voter,
+            Some(VoterWeightAction::CastVote),
+            None,
+        )
+        .await
+        .expect_err("records for votes on any proposal aren't written");
+
+    // all deposits count for actions without a rule
+    let vwr = addin
+        .update_voter_weight_record_for_action(
+            &registrar,
+            &voter,
+            Some(VoterWeightAction::CreateGovernance),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 7000);
+    assert_eq!(vwr.weight_action, Some(VoterWeightAction::CreateGovernance));
+    assert_eq!(vwr.weight_action_target, None);
+
+    // only the constant lockup is long enough to create proposals
+    let governance = Pubkey::new_unique();
+    let vwr = addin
+        .update_voter_weight_record_for_action(
+            &registrar,
+            &voter,
+            Some(VoterWeightAction::CreateProposal),
+            Some(governance),
+        )
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 4000);
+    assert_eq!(vwr.weight_action, Some(VoterWeightAction::CreateProposal));
+    assert_eq!(vwr.weight_action_target, Some(governance));
+
+    // records without an action would be accepted for votes
+    addin
+        .update_voter_weight_record_for_action(&registrar, &voter, None, None)
+        .await
+        .expect_err("records need an action");
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weight_checkpoints() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let grant_authority = &context.users[3].key;
+    let grant_funds = context.users[3].token_accounts[0];
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            Some(grant_authority.pubkey()),
+            None,
+        )
+        .await;
+
+    // the checkpoints are created with the voter
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    let t0 = context.solana.get_clock().await.unix_timestamp;
+    addin
+        .create_voter_weight_checkpoints(&registrar, &voter, payer)
+        .await
+        .expect_err("checkpoints already exist");
+
+    let deposit = |amount: u64| {
+        addin.deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            amount,
+        )
+    };
+
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let day = 24 * 60 * 60;
+    addin
+        .set_time_offset(&registrar, &realm_authority, day)
+        .await;
+    deposit(1000).await.unwrap();
+
+    addin
+        .set_time_offset(&registrar, &realm_authority, 2 * day)
+        .await;
+    deposit(500).await.unwrap();
+
+    addin
+        .set_time_offset(&registrar, &realm_authority, 3 * day)
+        .await;
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            300,
+        )
+        .await
+        .unwrap();
+
+    let checkpoints = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightCheckpoints>(
+            addin.voter_weight_checkpoints_address(&voter),
+        )
+        .await;
+    assert_eq!(checkpoints.voter, voter.address);
+    assert_eq!(checkpoints.len, 4);
+    assert_eq!(checkpoints.weight_at(t0 - 1), None);
+    assert_eq!(checkpoints.weight_at(t0 + day / 2), Some(0));
+    assert_eq!(checkpoints.weight_at(t0 + 3 * day / 2), Some(1000));
+    assert_eq!(checkpoints.weight_at(t0 + 5 * day / 2), Some(1500));
+    assert_eq!(checkpoints.weight_at(t0 + 4 * day), Some(1200));
+
+    addin
+        .get_voter_weight_at(&registrar, &voter, t0 + day / 2)
+        .await
+        .unwrap();
+    addin
+        .get_voter_weight_at(&registrar, &voter, t0 - 1)
+        .await
+        .expect_err("no checkpoint before creation");
+
+    // voters created by a grant get their checkpoints from anyone
+    let grantee = addin
+        .grant(
+            &registrar,
+            context.users[2].key.pubkey(),
+            &mngo_voting_mint,
+            LockupKind::Cliff,
+            None,
+            10,
+            false,
+            1000,
+            grant_funds,
+            grant_authority,
+            grant_authority,
+        )
+        .await
+        .unwrap();
+    addin
+        .get_voter_weight_at(&registrar, &grantee, t0 + 4 * day)
+        .await
+        .expect_err("grantee has no checkpoints");
+    addin
+        .create_voter_weight_checkpoints(&registrar, &grantee, payer)
+        .await
+        .unwrap();
+    addin
+        .get_voter_weight_at(&registrar, &grantee, t0 + 4 * day)
+        .await
+        .unwrap();
+
+    // closing the voter closes its checkpoints too
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            1200,
+        )
+        .await
+        .unwrap();
+    addin
+        .close_voter(&registrar, &voter, &mngo_voting_mint, voter_authority)
+        .await
+        .unwrap();
+    let checkpoints_lamports = context
+        .solana
+        .context
+        .borrow_mut()
+        .banks_client
+        .get_balance(addin.voter_weight_checkpoints_address(&voter))
+        .await?;
+    assert_eq!(checkpoints_lamports, 0);
+
+    Ok(())
+}
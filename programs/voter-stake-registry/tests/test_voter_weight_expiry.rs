@@ -0,0 +1,272 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::{LockupKind, VoterWeightExpiry, VoterWeightRecord};
+
+mod program_test;
+
+async fn get_record(solana: &SolanaCookie, address: Pubkey) -> VoterWeightRecord {
+    solana.get_account::<VoterWeightRecord>(address).await
+}
+
+async fn current_slot(solana: &SolanaCookie) -> u64 {
+    solana.get_clock().await.slot
+}
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weight_expiry() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let payer_mngo = context.users[0].token_accounts[0];
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let delegate_authority = &context.users[2].key;
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+    let token_owner_record2 = realm
+        .create_token_owner_record(delegate_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    let delegate = addin
+        .create_voter(
+            &registrar,
+            &token_owner_record2,
+            &delegate_authority,
+            &payer,
+        )
+        .await;
+    for (index, lockup_kind, allow_clawback, amount) in [
+        (0, LockupKind::None, false, 1000),
+        (1, LockupKind::Cliff, true, 500),
+    ] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                &voter_authority,
+                &mngo_voting_mint,
+                index,
+                lockup_kind,
+                None,
+                10,
+                allow_clawback,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                &voter_authority,
+                voter_mngo,
+                index,
+                amount,
+            )
+            .await
+            .unwrap();
+    }
+
+    // by default records expire in the current slot
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1500);
+    assert_eq!(
+        vwr.voter_weight_expiry,
+        Some(current_slot(&context.solana).await)
+    );
+
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &delegate,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .configure_voter_weight_expiry(&registrar, &voter_authority, VoterWeightExpiry::Slots, 100)
+        .await
+        .expect_err("only the realm authority can configure");
+    addin
+        .configure_voter_weight_expiry(&registrar, &realm_authority, VoterWeightExpiry::Slots, 100)
+        .await
+        .unwrap();
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(
+        vwr.voter_weight_expiry,
+        Some(current_slot(&context.solana).await + 100)
+    );
+
+    // weight reducing instructions rewrite the record
+    addin
+        .clawback(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &realm_authority,
+            payer_mngo,
+            1,
+            None,
+        )
+        .await
+        .unwrap();
+    let vwr = get_record(&context.solana, voter.voter_weight_record).await;
+    assert_eq!(vwr.voter_weight, 1000);
+    assert_eq!(
+        vwr.voter_weight_expiry,
+        Some(current_slot(&context.solana).await + 100)
+    );
+
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            0,
+            400,
+        )
+        .await
+        .unwrap();
+    let vwr = get_record(&context.solana, voter.voter_weight_record).await;
+    assert_eq!(vwr.voter_weight, 600);
+
+    addin
+        .configure_voter_weight_expiry(&registrar, &realm_authority, VoterWeightExpiry::Never, 0)
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 600);
+    assert_eq!(vwr.voter_weight_expiry, None);
+
+    addin
+        .set_delegate(
+            &registrar,
+            &voter,
+            &voter_authority,
+            delegate_authority.pubkey(),
+        )
+        .await
+        .unwrap();
+    let vwr = get_record(&context.solana, voter.voter_weight_record).await;
+    assert_eq!(vwr.voter_weight, 0);
+    assert_eq!(vwr.voter_weight_expiry, None);
+
+    // records with delegated weight still expire in the current slot
+    let vwr = addin
+        .update_voter_weight_record_with_delegations(
+            &registrar,
+            &delegate,
+            delegate_authority,
+            &[&voter],
+            Some(VoterWeightAction::CastVote),
+            Some(&proposal),
+        )
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 600);
+    assert_eq!(
+        vwr.voter_weight_expiry,
+        Some(current_slot(&context.solana).await)
+    );
+
+    // no vote was cast, so the voter's vote lock can be released
+    context.solana.advance_clock_by_slots(2).await;
+    addin
+        .release_vote_locks(
+            &registrar,
+            &voter,
+            &[realm.vote_record_address(&proposal, delegate.token_owner_record)],
+        )
+        .await
+        .unwrap();
+
+    addin
+        .set_delegate(&registrar, &voter, &voter_authority, Pubkey::default())
+        .await
+        .unwrap();
+    let vwr = get_record(&context.solana, voter.voter_weight_record).await;
+    assert_eq!(vwr.voter_weight, 600);
+
+    Ok(())
+}
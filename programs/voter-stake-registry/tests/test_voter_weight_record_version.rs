@@ -0,0 +1,273 @@
+use anchor_lang::AnchorDeserialize;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance::state::vote_record::VoteRecordV2;
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::{LockupKind, VoterWeightRecordVersion};
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weight_record_version() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let voter2_authority = &context.users[2].key;
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+    let token_owner_record2 = realm
+        .create_token_owner_record(voter2_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            &voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            0,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    // records are written in the v2 format by default
+    assert_eq!(
+        context
+            .solana
+            .get_account_data(voter.voter_weight_record)
+            .await[..8],
+        VoterWeightRecordVersion::DISCRIMINATOR_V2
+    );
+
+    addin
+        .configure_voter_weight_record_version(
+            &registrar,
+            &voter_authority,
+            VoterWeightRecordVersion::V3,
+        )
+        .await
+        .expect_err("only the realm authority can configure");
+    addin
+        .configure_voter_weight_record_version(
+            &registrar,
+            &realm_authority,
+            VoterWeightRecordVersion::V3,
+        )
+        .await
+        .unwrap();
+
+    // existing records switch format when they are written again
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+    assert_eq!(
+        context
+            .solana
+            .get_account_data(voter.voter_weight_record)
+            .await[..8],
+        VoterWeightRecordVersion::DISCRIMINATOR_V3
+    );
+
+    // new records are created in the configured format
+    let voter2 = addin
+        .create_voter(&registrar, &token_owner_record2, &voter2_authority, &payer)
+        .await;
+    assert_eq!(
+        context
+            .solana
+            .get_account_data(voter2.voter_weight_record)
+            .await[..8],
+        VoterWeightRecordVersion::DISCRIMINATOR_V3
+    );
+
+    // withdraws write the configured format too
+    addin
+        .configure_voter_weight_record_version(
+            &registrar,
+            &realm_authority,
+            VoterWeightRecordVersion::V2,
+        )
+        .await
+        .unwrap();
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            0,
+            400,
+        )
+        .await
+        .unwrap();
+    let vwr = context
+        .solana
+        .get_account::<voter_stake_registry::state::VoterWeightRecord>(voter.voter_weight_record)
+        .await;
+    assert_eq!(vwr.voter_weight, 600);
+    assert_eq!(
+        context
+            .solana
+            .get_account_data(voter.voter_weight_record)
+            .await[..8],
+        VoterWeightRecordVersion::DISCRIMINATOR_V2
+    );
+
+    // votes read the record in the configured format: the spl-governance 2.2.1
+    // program of the tests rejects v3 records
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            0,
+            400,
+        )
+        .await
+        .unwrap();
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+
+    addin
+        .configure_voter_weight_record_version(
+            &registrar,
+            &realm_authority,
+            VoterWeightRecordVersion::V3,
+        )
+        .await
+        .unwrap();
+    addin
+        .cast_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &voter,
+            &voter_authority,
+            payer,
+        )
+        .await
+        .expect_err("spl-governance 2.2.1 can't read v3 records");
+
+    addin
+        .configure_voter_weight_record_version(
+            &registrar,
+            &realm_authority,
+            VoterWeightRecordVersion::V2,
+        )
+        .await
+        .unwrap();
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .cast_vote(
+            &registrar,
+            &realm,
+            mint_governance.address,
+            &proposal,
+            &voter,
+            &voter_authority,
+            payer,
+        )
+        .await
+        .unwrap();
+    let vote_record_address = realm.vote_record_address(&proposal, voter.token_owner_record);
+    let vote_record_data = context.solana.get_account_data(vote_record_address).await;
+    let vote_record = VoteRecordV2::deserialize(&mut &vote_record_data[..]).unwrap();
+    assert_eq!(vote_record.voter_weight, 1000);
+
+    Ok(())
+}
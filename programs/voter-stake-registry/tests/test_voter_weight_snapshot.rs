@@ -0,0 +1,272 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weight_snapshot() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let voter2_authority = &context.users[2].key;
+    let voter2_mngo = context.users[2].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+    let token_owner_record2 = realm
+        .create_token_owner_record(voter2_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+    let voter2 = addin
+        .create_voter(&registrar, &token_owner_record2, &voter2_authority, &payer)
+        .await;
+
+    let mint_governance = realm
+        .create_mint_governance(
+            context.mints[0].pubkey.unwrap(),
+            &context.mints[0].authority,
+            &voter,
+            &voter_authority,
+            payer,
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
+        )
+        .await;
+
+    for (voter, authority, token_account) in [
+        (&voter, voter_authority, voter_mngo),
+        (&voter2, voter2_authority, voter2_mngo),
+    ] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                voter,
+                authority,
+                &mngo_voting_mint,
+                0,
+                LockupKind::None,
+                None,
+                0,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                voter,
+                &mngo_voting_mint,
+                authority,
+                token_account,
+                0,
+                1000,
+            )
+            .await
+            .unwrap();
+    }
+
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+    // later changes to the deposits are after the proposal's voting_at
+    context.solana.advance_clock_by_secs(60).await;
+
+    let snapshot = addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter2,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot.voter, voter2.address);
+    assert_eq!(snapshot.proposal, proposal.address);
+    assert_eq!(snapshot.voter_weight, 1000);
+
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter2,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .expect_err("snapshot already exists");
+
+    // locking the weight for the vote needs the voter authority's signature
+    let mut instruction =
+        addin.update_voter_weight_record_for_vote_instruction(&registrar, &voter2, &proposal);
+    instruction.accounts.last_mut().unwrap().is_signer = false;
+    context
+        .solana
+        .process_transaction(&[instruction], None)
+        .await
+        .expect_err("voter authority must sign");
+
+    // tokens withdrawn after the snapshot don't count on the proposal either
+    context.solana.advance_clock_by_slots(2).await;
+    addin
+        .withdraw(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            voter2_authority,
+            voter2_mngo,
+            0,
+            600,
+        )
+        .await
+        .unwrap();
+    let vwr = addin
+        .update_voter_weight_record_for_vote(&registrar, &voter2, voter2_authority, &proposal)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 400);
+
+    // tokens deposited after the snapshot don't count on the proposal
+    addin
+        .deposit(
+            &registrar,
+            &voter2,
+            &mngo_voting_mint,
+            voter2_authority,
+            voter2_mngo,
+            0,
+            5000,
+        )
+        .await
+        .unwrap();
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter2)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 5400);
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    let vwr = addin
+        .update_voter_weight_record_for_vote(&registrar, &voter2, voter2_authority, &proposal)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+
+    // voters can't vote without a snapshot, and it's taken from the
+    // history of their weight, not their current deposits
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            5000,
+        )
+        .await
+        .unwrap();
+    addin
+        .update_voter_weight_record_for_vote(&registrar, &voter, voter_authority, &proposal)
+        .await
+        .expect_err("snapshot required");
+    let snapshot = addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
+    assert_eq!(snapshot.voter_weight, 1000);
+    let vwr = addin
+        .update_voter_weight_record_for_vote(&registrar, &voter, voter_authority, &proposal)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 1000);
+
+    // records without an action, or for CastVote without a target, would be
+    // accepted for any vote
+    context.solana.advance_clock_by_slots(2).await;
+    addin
+        .update_voter_weight_record_for_action(&registrar, &voter, None, None)
+        .await
+        .expect_err("action required");
+    addin
+        .update_voter_weight_record_for_action(
+            &registrar,
+            &voter,
+            Some(VoterWeightAction::CastVote),
+            None,
+        )
+        .await
+        .expect_err("proposal required");
+
+    realm
+        .cast_vote(
+            mint_governance.address,
+            &proposal,
+            &voter2,
+            &voter2_authority,
+            payer,
+            addin.update_voter_weight_record_for_vote_instruction(&registrar, &voter2, &proposal),
+        )
+        .await
+        .unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voter_weights_cpi() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+
+    let day = 24 * 60 * 60;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            1.0,
+            10 * day,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    for (index, kind, periods) in [(0, LockupKind::None, 0), (1, LockupKind::Constant, 10)] {
+        addin
+            .create_deposit_entry(
+                &registrar,
+                &voter,
+                voter_authority,
+                &mngo_voting_mint,
+                index,
+                kind,
+                None,
+                periods,
+                false,
+            )
+            .await
+            .unwrap();
+        addin
+            .deposit(
+                &registrar,
+                &voter,
+                &mngo_voting_mint,
+                voter_authority,
+                voter_mngo,
+                index,
+                1000,
+            )
+            .await
+            .unwrap();
+    }
+
+    let now = context.solana.get_clock().await.unix_timestamp;
+
+    let weights = addin
+        .get_voter_weights_via_cpi(&registrar, &voter, now)
+        .await
+        .unwrap();
+    assert_eq!(weights.weight, 3000);
+    assert_eq!(weights.weight_baseline, 2000);
+    assert_eq!(weights.weight_locked_guaranteed, 1000);
+
+    // the constant lockup could be unlocking starting now
+    let weights = addin
+        .get_voter_weights_via_cpi(&registrar, &voter, now + 5 * day as i64)
+        .await
+        .unwrap();
+    assert_eq!(weights.weight, 3000);
+    assert_eq!(weights.weight_baseline, 2000);
+    assert_eq!(weights.weight_locked_guaranteed, 500);
+
+    addin
+        .get_voter_weights_via_cpi(&registrar, &voter, now - 1)
+        .await
+        .expect_err("at_ts can't be in the past");
+
+    Ok(())
+}
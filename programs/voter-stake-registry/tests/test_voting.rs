@@ -2,6 +2,7 @@ use anchor_spl::token::TokenAccount;
 use program_test::*;
 use solana_program_test::*;
 use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use spl_governance_addin_api::voter_weight::VoterWeightAction;
 use voter_stake_registry::state::LockupKind;
 
 mod program_test;
@@ -84,7 +85,12 @@ async fn test_voting() -> Result<(), TransportError> {
             &voter,
             &voter_authority,
             payer,
-            addin.update_voter_weight_record_instruction(&registrar, &voter),
+            addin.update_voter_weight_record_for_action_instruction(
+                &registrar,
+                &voter,
+                Some(VoterWeightAction::CreateGovernance),
+                None,
+            ),
         )
         .await;
 
@@ -141,32 +147,7 @@ async fn test_voting() -> Result<(), TransportError> {
         .unwrap();
     context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
 
-    let proposal = realm
-        .create_proposal(
-            mint_governance.address,
-            &voter_authority,
-            &voter,
-            payer,
-            addin.update_voter_weight_record_instruction(&registrar, &voter),
-        )
-        .await
-        .unwrap();
-
-    // having created a proposal, withdrawing is impossible
-    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
-    addin
-        .withdraw(
-            &registrar,
-            &voter,
-            &mngo_voting_mint,
-            &voter_authority,
-            voter_mngo,
-            0,
-            1,
-        )
-        .await
-        .expect_err("could not withdraw");
-
+    // voter2's deposits must predate the proposal to count on it
     addin
         .create_deposit_entry(
             &registrar,
@@ -221,6 +202,42 @@ async fn test_voting() -> Result<(), TransportError> {
         .await
         .unwrap();
 
+    let proposal = realm
+        .create_proposal(
+            mint_governance.address,
+            &voter_authority,
+            &voter,
+            payer,
+            addin.update_voter_weight_record_instruction(&registrar, &voter),
+        )
+        .await
+        .unwrap();
+
+    // having created a proposal, withdrawing is impossible
+    context.solana.advance_clock_by_slots(2).await; // avoid cache when sending same transaction again
+    addin
+        .withdraw(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            &voter_authority,
+            voter_mngo,
+            0,
+            1,
+        )
+        .await
+        .expect_err("could not withdraw");
+
+    addin
+        .create_voter_weight_snapshot(
+            &registrar,
+            &voter2,
+            mint_governance.address,
+            proposal.address,
+            payer,
+        )
+        .await
+        .unwrap();
     realm
         .cast_vote(
             mint_governance.address,
@@ -228,7 +245,7 @@ async fn test_voting() -> Result<(), TransportError> {
             &voter2,
             &voter2_authority,
             payer,
-            addin.update_voter_weight_record_instruction(&registrar, &voter2),
+            addin.update_voter_weight_record_for_vote_instruction(&registrar, &voter2, &proposal),
         )
         .await
         .unwrap();
@@ -279,6 +296,14 @@ async fn test_voting() -> Result<(), TransportError> {
         )
         .await
         .unwrap();
+    addin
+        .release_vote_locks(
+            &registrar,
+            &voter2,
+            &[realm.vote_record_address(&proposal, voter2.token_owner_record)],
+        )
+        .await
+        .unwrap();
 
     // can withdraw again
     addin
@@ -0,0 +1,191 @@
+use anchor_spl::token::TokenAccount;
+use program_test::*;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, transport::TransportError};
+use voter_stake_registry::state::LockupKind;
+
+mod program_test;
+
+#[allow(unaligned_references)]
+#[tokio::test]
+async fn test_voting_mint_schedule() -> Result<(), TransportError> {
+    let context = TestContext::new().await;
+    let addin = &context.addin;
+
+    let payer = &context.users[0].key;
+    let realm_authority = Keypair::new();
+    let realm = context
+        .governance
+        .create_realm(
+            "testrealm",
+            realm_authority.pubkey(),
+            &context.mints[0],
+            &payer,
+            &context.addin.program_id,
+        )
+        .await;
+
+    let voter_authority = &context.users[1].key;
+    let voter_mngo = context.users[1].token_accounts[0];
+    let token_owner_record = realm
+        .create_token_owner_record(voter_authority.pubkey(), &payer)
+        .await;
+
+    let registrar = addin
+        .create_registrar(&realm, &realm_authority, payer)
+        .await;
+    let mngo_voting_mint = addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+
+    let voter = addin
+        .create_voter(&registrar, &token_owner_record, &voter_authority, &payer)
+        .await;
+
+    addin
+        .create_deposit_entry(
+            &registrar,
+            &voter,
+            voter_authority,
+            &mngo_voting_mint,
+            0,
+            LockupKind::None,
+            None,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    addin
+        .deposit(
+            &registrar,
+            &voter,
+            &mngo_voting_mint,
+            voter_authority,
+            voter_mngo,
+            0,
+            10000,
+        )
+        .await
+        .unwrap();
+
+    let day = 24 * 60 * 60;
+    let start = context.solana.get_clock().await.unix_timestamp + 10 * day;
+    let end = start + 100 * day;
+
+    addin
+        .configure_voting_mint_schedule(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            0.0,
+            0.0,
+            end,
+            start,
+            None,
+        )
+        .await
+        .expect_err("schedule must end after it starts");
+
+    addin
+        .configure_voting_mint_schedule(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            0.0,
+            0.0,
+            start - 20 * day,
+            end,
+            None,
+        )
+        .await
+        .expect_err("schedule can't start in the past");
+
+    // phase out the vote weight over 100 days
+    addin
+        .configure_voting_mint_schedule(
+            &registrar,
+            &realm_authority,
+            0,
+            &mngo_voting_mint,
+            0.0,
+            0.0,
+            start,
+            end,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 10000);
+
+    let expected_weight = |ts: i64| {
+        let factor = 1_000_000_000u64 - 1_000_000_000u64 * (ts - start) as u64 / (100 * day) as u64;
+        10000 * factor / 1_000_000_000
+    };
+
+    addin
+        .set_time_offset(&registrar, &realm_authority, 60 * day)
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+    let ts = context.solana.get_clock().await.unix_timestamp + 60 * day;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, expected_weight(ts));
+    assert!(vwr.voter_weight < 5000 && vwr.voter_weight > 4900);
+
+    addin
+        .set_time_offset(&registrar, &realm_authority, 200 * day)
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 0);
+
+    // reconfiguring removes the schedule
+    addin
+        .configure_voting_mint(
+            &registrar,
+            &realm_authority,
+            payer,
+            0,
+            &context.mints[0],
+            0,
+            1.0,
+            0.0,
+            5 * 365 * 24 * 60 * 60,
+            None,
+            None,
+        )
+        .await;
+    context.solana.advance_clock_by_slots(2).await;
+    let vwr = addin
+        .update_voter_weight_record(&registrar, &voter)
+        .await
+        .unwrap();
+    assert_eq!(vwr.voter_weight, 10000);
+
+    Ok(())
+}